@@ -1,25 +1,47 @@
 use anchor_lang::prelude::AccountMeta;
+use anyhow::{Result, bail};
 use solana_sdk::pubkey::Pubkey;
 
-pub(crate) struct DarklakeAmmSwap {
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DarklakeAmmSwap {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub authority: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub amm_config: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_wsol_reserve: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub order: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub associated_token_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub system_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_program: Pubkey,
 }
 
@@ -49,28 +71,83 @@ impl From<DarklakeAmmSwap> for Vec<AccountMeta> {
     }
 }
 
-pub(crate) struct DarklakeAmmSettle {
+impl DarklakeAmmSwap {
+    pub const ACCOUNTS_LEN: usize = 19;
+
+    /// Rebuild the named accounts from the ordered instruction account keys
+    pub fn try_from_account_keys(keys: &[Pubkey]) -> Result<Self> {
+        check_account_keys_len(keys, Self::ACCOUNTS_LEN)?;
+
+        Ok(Self {
+            user: keys[0],
+            token_mint_x: keys[1],
+            token_mint_y: keys[2],
+            token_mint_wsol: keys[3],
+            pool: keys[4],
+            authority: keys[5],
+            amm_config: keys[6],
+            user_token_account_x: keys[7],
+            user_token_account_y: keys[8],
+            user_token_account_wsol: keys[9],
+            pool_token_reserve_x: keys[10],
+            pool_token_reserve_y: keys[11],
+            pool_wsol_reserve: keys[12],
+            order: keys[13],
+            associated_token_program: keys[14],
+            system_program: keys[15],
+            token_mint_x_program: keys[16],
+            token_mint_y_program: keys[17],
+            token_program: keys[18],
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DarklakeAmmSettle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub caller: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub order_owner: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub authority: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_wsol_reserve: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub amm_config: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub caller_token_account_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub order: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub order_token_account_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub system_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub associated_token_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_program: Pubkey,
 }
 
@@ -103,27 +180,84 @@ impl From<DarklakeAmmSettle> for Vec<AccountMeta> {
     }
 }
 
-pub(crate) struct DarklakeAmmCancel {
+impl DarklakeAmmSettle {
+    pub const ACCOUNTS_LEN: usize = 22;
+
+    /// Rebuild the named accounts from the ordered instruction account keys
+    pub fn try_from_account_keys(keys: &[Pubkey]) -> Result<Self> {
+        check_account_keys_len(keys, Self::ACCOUNTS_LEN)?;
+
+        Ok(Self {
+            caller: keys[0],
+            order_owner: keys[1],
+            token_mint_x: keys[2],
+            token_mint_y: keys[3],
+            token_mint_wsol: keys[4],
+            pool: keys[5],
+            authority: keys[6],
+            pool_token_reserve_x: keys[7],
+            pool_token_reserve_y: keys[8],
+            pool_wsol_reserve: keys[9],
+            amm_config: keys[10],
+            user_token_account_x: keys[11],
+            user_token_account_y: keys[12],
+            user_token_account_wsol: keys[13],
+            caller_token_account_wsol: keys[14],
+            order: keys[15],
+            order_token_account_wsol: keys[16],
+            system_program: keys[17],
+            associated_token_program: keys[18],
+            token_mint_x_program: keys[19],
+            token_mint_y_program: keys[20],
+            token_program: keys[21],
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DarklakeAmmCancel {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub caller: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub order_owner: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub authority: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_wsol_reserve: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub amm_config: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub caller_token_account_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub order: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub system_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub associated_token_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_program: Pubkey,
 }
 
@@ -155,26 +289,81 @@ impl From<DarklakeAmmCancel> for Vec<AccountMeta> {
     }
 }
 
-pub(crate) struct DarklakeAmmSlash {
+impl DarklakeAmmCancel {
+    pub const ACCOUNTS_LEN: usize = 21;
+
+    /// Rebuild the named accounts from the ordered instruction account keys
+    pub fn try_from_account_keys(keys: &[Pubkey]) -> Result<Self> {
+        check_account_keys_len(keys, Self::ACCOUNTS_LEN)?;
+
+        Ok(Self {
+            caller: keys[0],
+            order_owner: keys[1],
+            token_mint_x: keys[2],
+            token_mint_y: keys[3],
+            token_mint_wsol: keys[4],
+            pool: keys[5],
+            authority: keys[6],
+            pool_token_reserve_x: keys[7],
+            pool_token_reserve_y: keys[8],
+            pool_wsol_reserve: keys[9],
+            amm_config: keys[10],
+            user_token_account_x: keys[11],
+            user_token_account_y: keys[12],
+            user_token_account_wsol: keys[13],
+            caller_token_account_wsol: keys[14],
+            order: keys[15],
+            system_program: keys[16],
+            associated_token_program: keys[17],
+            token_mint_x_program: keys[18],
+            token_mint_y_program: keys[19],
+            token_program: keys[20],
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DarklakeAmmSlash {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub caller: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub order_owner: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub authority: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_wsol_reserve: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub amm_config: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub caller_token_account_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub order: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub system_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub associated_token_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_program: Pubkey,
 }
 
@@ -205,23 +394,74 @@ impl From<DarklakeAmmSlash> for Vec<AccountMeta> {
     }
 }
 
-pub(crate) struct DarklakeAmmAddLiquidity {
+impl DarklakeAmmSlash {
+    pub const ACCOUNTS_LEN: usize = 20;
+
+    /// Rebuild the named accounts from the ordered instruction account keys
+    pub fn try_from_account_keys(keys: &[Pubkey]) -> Result<Self> {
+        check_account_keys_len(keys, Self::ACCOUNTS_LEN)?;
+
+        Ok(Self {
+            caller: keys[0],
+            order_owner: keys[1],
+            token_mint_x: keys[2],
+            token_mint_y: keys[3],
+            token_mint_wsol: keys[4],
+            pool: keys[5],
+            authority: keys[6],
+            pool_token_reserve_x: keys[7],
+            pool_token_reserve_y: keys[8],
+            pool_wsol_reserve: keys[9],
+            amm_config: keys[10],
+            user_token_account_x: keys[11],
+            user_token_account_y: keys[12],
+            caller_token_account_wsol: keys[13],
+            order: keys[14],
+            system_program: keys[15],
+            associated_token_program: keys[16],
+            token_mint_x_program: keys[17],
+            token_mint_y_program: keys[18],
+            token_program: keys[19],
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DarklakeAmmAddLiquidity {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_lp: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub authority: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub amm_config: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_lp: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub associated_token_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub system_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_program: Pubkey,
 }
 
@@ -249,23 +489,71 @@ impl From<DarklakeAmmAddLiquidity> for Vec<AccountMeta> {
     }
 }
 
-pub(crate) struct DarklakeAmmRemoveLiquidity {
+impl DarklakeAmmAddLiquidity {
+    pub const ACCOUNTS_LEN: usize = 17;
+
+    /// Rebuild the named accounts from the ordered instruction account keys
+    pub fn try_from_account_keys(keys: &[Pubkey]) -> Result<Self> {
+        check_account_keys_len(keys, Self::ACCOUNTS_LEN)?;
+
+        Ok(Self {
+            user: keys[0],
+            token_mint_x: keys[1],
+            token_mint_y: keys[2],
+            token_mint_lp: keys[3],
+            pool: keys[4],
+            amm_config: keys[5],
+            authority: keys[6],
+            user_token_account_x: keys[7],
+            user_token_account_y: keys[8],
+            user_token_account_lp: keys[9],
+            pool_token_reserve_x: keys[10],
+            pool_token_reserve_y: keys[11],
+            associated_token_program: keys[12],
+            system_program: keys[13],
+            token_mint_x_program: keys[14],
+            token_mint_y_program: keys[15],
+            token_program: keys[16],
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DarklakeAmmRemoveLiquidity {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_lp: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub authority: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub amm_config: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_lp: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub associated_token_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub system_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_program: Pubkey,
 }
 
@@ -293,31 +581,87 @@ impl From<DarklakeAmmRemoveLiquidity> for Vec<AccountMeta> {
     }
 }
 
-pub(crate) struct DarklakeAmmInitializePool {
+impl DarklakeAmmRemoveLiquidity {
+    pub const ACCOUNTS_LEN: usize = 17;
+
+    /// Rebuild the named accounts from the ordered instruction account keys
+    pub fn try_from_account_keys(keys: &[Pubkey]) -> Result<Self> {
+        check_account_keys_len(keys, Self::ACCOUNTS_LEN)?;
+
+        Ok(Self {
+            user: keys[0],
+            token_mint_x: keys[1],
+            token_mint_y: keys[2],
+            amm_config: keys[3],
+            token_mint_lp: keys[4],
+            pool: keys[5],
+            authority: keys[6],
+            user_token_account_x: keys[7],
+            user_token_account_y: keys[8],
+            user_token_account_lp: keys[9],
+            pool_token_reserve_x: keys[10],
+            pool_token_reserve_y: keys[11],
+            associated_token_program: keys[12],
+            system_program: keys[13],
+            token_mint_x_program: keys[14],
+            token_mint_y_program: keys[15],
+            token_program: keys[16],
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DarklakeAmmInitializePool {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub authority: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub amm_config: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_wsol: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_lp: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub metadata_account: Pubkey, // lp
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub metadata_account_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub metadata_account_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user_token_account_lp: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_token_reserve_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub pool_wsol_reserve: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub create_pool_fee_vault: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub mpl_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub system_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub rent: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub associated_token_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_program: Pubkey,
 }
 
@@ -352,3 +696,51 @@ impl From<DarklakeAmmInitializePool> for Vec<AccountMeta> {
         ]
     }
 }
+
+impl DarklakeAmmInitializePool {
+    pub const ACCOUNTS_LEN: usize = 25;
+
+    /// Rebuild the named accounts from the ordered instruction account keys
+    pub fn try_from_account_keys(keys: &[Pubkey]) -> Result<Self> {
+        check_account_keys_len(keys, Self::ACCOUNTS_LEN)?;
+
+        Ok(Self {
+            user: keys[0],
+            pool: keys[1],
+            authority: keys[2],
+            amm_config: keys[3],
+            token_mint_x: keys[4],
+            token_mint_y: keys[5],
+            token_mint_wsol: keys[6],
+            token_mint_lp: keys[7],
+            metadata_account: keys[8],
+            metadata_account_x: keys[9],
+            metadata_account_y: keys[10],
+            user_token_account_x: keys[11],
+            user_token_account_y: keys[12],
+            user_token_account_lp: keys[13],
+            pool_token_reserve_x: keys[14],
+            pool_token_reserve_y: keys[15],
+            pool_wsol_reserve: keys[16],
+            create_pool_fee_vault: keys[17],
+            mpl_program: keys[18],
+            system_program: keys[19],
+            rent: keys[20],
+            associated_token_program: keys[21],
+            token_mint_x_program: keys[22],
+            token_mint_y_program: keys[23],
+            token_program: keys[24],
+        })
+    }
+}
+
+fn check_account_keys_len(keys: &[Pubkey], expected: usize) -> Result<()> {
+    if keys.len() < expected {
+        bail!(
+            "Not enough accounts, expected at least {} but got {}",
+            expected,
+            keys.len()
+        );
+    }
+    Ok(())
+}
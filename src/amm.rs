@@ -1,8 +1,13 @@
 use anchor_lang::prelude::AccountMeta;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize, prelude::borsh};
 use anyhow::Result;
 use solana_sdk::pubkey::Pubkey;
+use std::fmt;
 
-use crate::{darklake_amm::Order, proof::proof_generator::GeneratedProof};
+use crate::{
+    darklake_amm::{AmmConfig, Pool},
+    proof::proof_generator::GeneratedProof,
+};
 
 /// Core AMM trait for Darklake DEX operations
 pub(crate) trait Amm: Send + Sync {
@@ -53,12 +58,6 @@ pub(crate) trait Amm: Send + Sync {
     /// Get order pubkey
     fn get_order_pubkey(&self, user: &Pubkey) -> Result<Pubkey>;
 
-    /// Get order output
-    fn parse_order_data(&self, order_data: &[u8]) -> Result<Order>;
-
-    /// Check if order is expired
-    fn is_order_expired(&self, order_data: &[u8], current_slot: u64) -> Result<bool>;
-
     /// Get cancel parameters and account metadata
     fn get_cancel_and_account_metas(
         &self,
@@ -76,7 +75,6 @@ pub(crate) trait Amm: Send + Sync {
     fn get_initialize_pool_and_account_metas(
         &self,
         initialize_pool_params: &InitializePoolParams,
-        is_devnet: bool,
     ) -> Result<InitializePoolAndAccountMetas>;
 
     /// Get add liquidity parameters and account metadata
@@ -97,8 +95,11 @@ pub type AccountMap = std::collections::HashMap<Pubkey, AccountData>;
 
 /// Account data structure
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccountData {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_vec"))]
     pub data: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub owner: Pubkey,
 }
 
@@ -113,18 +114,180 @@ pub struct QuoteParams {
 
 /// Swap mode (exact in/out)
 #[derive(Debug, Clone, PartialEq, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SwapMode {
     ExactIn,
 }
 
 /// Quote result
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Quote {
     pub in_amount: u64,
     pub out_amount: u64,
     pub fee_amount: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub fee_mint: Pubkey,
+    /// Trade fee rate in percent, e.g. 0.3 for a 0.3% fee. Breaking: versions up to 0.5.0
+    /// returned the raw `trade_fee_rate` (3000 for 0.3%)
     pub fee_pct: rust_decimal::Decimal,
+    /// Part of the trade fee going to the protocol, in the fee mint
+    pub protocol_fee: u64,
+    /// Token-2022 transfer fee charged on the input amount
+    pub input_transfer_fee: u64,
+    /// Token-2022 transfer fee charged on the output amount
+    pub output_transfer_fee: u64,
+    /// Output tokens per input token before the trade, in base units
+    pub spot_price: rust_decimal::Decimal,
+    /// Output tokens received per input token spent, in base units, fees included
+    pub execution_price: rust_decimal::Decimal,
+    /// Difference between the spot price and the price of the curve in basis points, excluding
+    /// the trade fee and transfer fees so a tiny trade has no impact
+    pub price_impact_bps: u64,
+}
+
+/// Pool account together with its reserve balances
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolInfo {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub key: Pubkey,
+    pub pool: Pool,
+    pub reserve_x_balance: u64,
+    pub reserve_y_balance: u64,
+}
+
+/// AmmConfig (fee tier) account together with its address
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AmmConfigInfo {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub key: Pubkey,
+    pub amm_config: AmmConfig,
+}
+
+/// Whether a pool can be traded and how much of its reserves is available
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolStatus {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub key: Pubkey,
+    /// Whether the pool's AmmConfig is halted
+    pub halted: bool,
+    pub reserve_x_balance: u64,
+    pub reserve_y_balance: u64,
+    /// Reserves excluding fees and amounts locked in open orders
+    pub available_reserve_x: u64,
+    pub available_reserve_y: u64,
+    pub token_lp_supply: u64,
+}
+
+/// A problem found before building a transaction that would make it fail on chain
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PreflightIssue {
+    /// The user's associated token account for `mint` does not exist
+    MissingTokenAccount {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+        mint: Pubkey,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+        token_account: Pubkey,
+    },
+    /// The user's token account holds less than the transaction transfers
+    InsufficientTokenBalance {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+        mint: Pubkey,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+        token_account: Pubkey,
+        required: u64,
+        available: u64,
+    },
+    /// The user has fewer lamports than the SOL input, order deposit and rent of new accounts
+    InsufficientSol { required: u64, available: u64 },
+}
+
+impl fmt::Display for PreflightIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreflightIssue::MissingTokenAccount {
+                mint,
+                token_account,
+            } => write!(
+                f,
+                "Token account {} for mint {} does not exist",
+                token_account, mint
+            ),
+            PreflightIssue::InsufficientTokenBalance {
+                mint,
+                token_account,
+                required,
+                available,
+            } => write!(
+                f,
+                "Token account {} for mint {} holds {}, {} required",
+                token_account, mint, available, required
+            ),
+            PreflightIssue::InsufficientSol {
+                required,
+                available,
+            } => write!(
+                f,
+                "Insufficient SOL, {} lamports available, {} required",
+                available, required
+            ),
+        }
+    }
+}
+
+/// Token amounts exchanged for LP tokens when adding or removing liquidity
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LiquidityQuote {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_y: Pubkey,
+    pub amount_x: u64,
+    pub amount_y: u64,
+    pub amount_lp: u64,
+}
+
+/// A user's LP holdings in a pool and their underlying value
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LpPosition {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_lp: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_y: Pubkey,
+    pub amount_lp: u64,
+    pub lp_supply: u64,
+    /// Share of the pool owned, between 0 and 1
+    pub share: rust_decimal::Decimal,
+    /// Underlying tokens returned when burning `amount_lp`
+    pub amount_x: u64,
+    pub amount_y: u64,
+    /// Share of the accumulated LP fees
+    pub lp_fee_x: u64,
+    pub lp_fee_y: u64,
+}
+
+/// Split of a single token deposit into a swap and a liquidity deposit
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZapQuote {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_in: Pubkey,
+    pub amount_in: u64,
+    /// Part of `amount_in` to swap into the other pool token
+    pub swap_amount: u64,
+    pub swap_quote: Quote,
+    /// Expected deposit once the swap has settled
+    pub liquidity_quote: LiquidityQuote,
 }
 
 /// Swap parameters
@@ -195,6 +358,7 @@ pub struct AddLiquidityParams {
 #[derive(Debug, Clone)]
 pub struct InitializePoolParams {
     pub user: Pubkey,
+    pub amm_config: Pubkey,
     pub token_x: Pubkey,
     pub token_x_program: Pubkey,
     pub token_y: Pubkey,
@@ -214,22 +378,6 @@ pub struct RemoveLiquidityParams {
     pub label: Option<[u8; 21]>,
 }
 
-/// Finalize parameters
-#[derive(Debug, Clone)]
-pub struct FinalizeParams {
-    pub settle_signer: Pubkey,
-    pub order_owner: Pubkey,
-    pub unwrap_wsol: bool,
-    pub min_out: u64,
-    pub salt: [u8; 8],
-    pub output: u64,
-    pub commitment: [u8; 32],
-    pub deadline: u64,
-    pub current_slot: u64,
-    pub ref_code: Option<[u8; 20]>,
-    pub label: Option<[u8; 21]>,
-}
-
 /// Swap result with account metadata
 #[derive(Debug, Clone)]
 pub struct SwapAndAccountMetas {
@@ -322,66 +470,118 @@ pub struct InitializePoolAndAccountMetas {
 }
 
 /// Darklake AMM swap parameters
-#[derive(Debug, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DarklakeAmmSwapParams {
     pub amount_in: u64,
     pub is_swap_x_to_y: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub c_min: [u8; 32],
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
     pub label: Option<[u8; 21]>,
 }
 
 /// Darklake AMM settle parameters
-#[derive(Debug, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DarklakeAmmSettleParams {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub proof_a: [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub proof_b: [u8; 128],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub proof_c: [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes_array"))]
     pub public_signals: [[u8; 32]; 2],
     pub unwrap_wsol: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
     pub ref_code: Option<[u8; 20]>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
     pub label: Option<[u8; 21]>,
 }
 
 /// Darklake AMM settle parameters
-#[derive(Debug, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DarklakeAmmCancelParams {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub proof_a: [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub proof_b: [u8; 128],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub proof_c: [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes_array"))]
     pub public_signals: [[u8; 32]; 2],
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
     pub label: Option<[u8; 21]>,
 }
 
 /// Darklake AMM slash parameters
-#[derive(Debug, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DarklakeAmmSlashParams {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
     pub label: Option<[u8; 21]>,
 }
 
 /// Darklake AMM initialize pool parameters
-#[derive(Debug, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DarklakeAmmInitializePoolParams {
     pub amount_x: u64,
     pub amount_y: u64,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
     pub label: Option<[u8; 21]>,
 }
 
 /// Darklake AMM add liquidity parameters
-#[derive(Debug, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DarklakeAmmAddLiquidityParams {
     pub amount_lp: u64,
     pub max_amount_x: u64,
     pub max_amount_y: u64,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
     pub ref_code: Option<[u8; 20]>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
     pub label: Option<[u8; 21]>,
 }
 
 /// Darklake AMM remove liquidity parameters
-#[derive(Debug, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DarklakeAmmRemoveLiquidityParams {
     pub amount_lp: u64,
     pub min_amount_x: u64,
     pub min_amount_y: u64,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
     pub label: Option<[u8; 21]>,
 }
 
@@ -400,20 +600,12 @@ pub(crate) struct ProofCircuitPaths {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProofParams {
     pub generated_proof: GeneratedProof,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes_array"))]
     pub public_inputs: [[u8; 32]; 2],
 }
-/// Helper function to get account data from account map
-pub(crate) fn try_get_account_data<'a>(
-    account_map: &'a AccountMap,
-    pubkey: &Pubkey,
-) -> Result<&'a [u8]> {
-    account_map
-        .get(pubkey)
-        .map(|account| account.data.as_slice())
-        .ok_or_else(|| anyhow::anyhow!("Account not found: {}", pubkey))
-}
 
 /// Helper function to get account data and owner from account map
 pub(crate) fn try_get_account_data_and_owner<'a>(
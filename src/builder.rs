@@ -0,0 +1,294 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{Result, bail};
+use solana_rpc_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::RpcClientConfig, rpc_sender::RpcSender,
+};
+use solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    chain_reader::ChainReader, constants::DEFAULT_AMM_CONFIG_INDEX,
+    lookup_table_cache::DEFAULT_LOOKUP_TABLE_TTL, network::DarklakeNetworkConfig,
+    nonce::DurableNonceConfig, sdk::DarklakeSDK,
+};
+
+/// Creates the RPC client of a `rpc_sender` once the commitment level is known
+type RpcSenderClient = Box<dyn FnOnce(RpcClientConfig) -> RpcClient + Send>;
+
+/// Compute budget applied to the transactions built by the `_tx` functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxDefaults {
+    /// Compute unit limit of swap transactions
+    pub swap_compute_unit_limit: u32,
+    /// Compute unit limit of settle, cancel, slash and pool initialization transactions,
+    /// liquidity transactions keep the runtime default
+    pub compute_unit_limit: u32,
+    /// Priority fee in micro-lamports per compute unit for every transaction, no
+    /// `SetComputeUnitPrice` instruction is added when `None`
+    pub compute_unit_price: Option<u64>,
+}
+
+impl Default for TxDefaults {
+    fn default() -> Self {
+        Self {
+            swap_compute_unit_limit: 300_000,
+            compute_unit_limit: 500_000,
+            compute_unit_price: None,
+        }
+    }
+}
+
+/// Builder for a `DarklakeSDK` with a caller supplied RPC client
+///
+/// At most one of `rpc_endpoint`, `rpc_client` or `rpc_sender` can be set. Chain reads go through
+/// it unless a `chain_reader` is set, and `execute_route` / `execute_zap` send their
+/// transactions through it. Without an RPC source a `chain_reader` is required and those two
+/// functions fail.
+///
+/// The other settings default to mainnet, confirmed commitment, no label or ref code, the circuits
+/// bundled with the crate, the default AmmConfig, `TxDefaults::default()`, a 5 minute lookup table
+/// cache, no extra lookup tables and recent blockhashes instead of a durable nonce.
+pub struct DarklakeSDKBuilder {
+    pub(crate) rpc_client: Option<Arc<RpcClient>>,
+    pub(crate) rpc_endpoint: Option<String>,
+    pub(crate) rpc_sender: Option<RpcSenderClient>,
+    pub(crate) chain_reader: Option<Arc<dyn ChainReader>>,
+    pub(crate) commitment_level: CommitmentLevel,
+    pub(crate) network: DarklakeNetworkConfig,
+    pub(crate) label: Option<String>,
+    pub(crate) ref_code: Option<String>,
+    pub(crate) circuits_dir: Option<String>,
+    pub(crate) amm_config_index: u32,
+    pub(crate) slash_expired_orders: bool,
+    pub(crate) tx_defaults: TxDefaults,
+    pub(crate) lookup_table_ttl: Duration,
+    pub(crate) extra_lookup_tables: Vec<Pubkey>,
+    pub(crate) durable_nonce: Option<DurableNonceConfig>,
+}
+
+impl Default for DarklakeSDKBuilder {
+    fn default() -> Self {
+        Self {
+            rpc_client: None,
+            rpc_endpoint: None,
+            rpc_sender: None,
+            chain_reader: None,
+            commitment_level: CommitmentLevel::Confirmed,
+            network: DarklakeNetworkConfig::mainnet(),
+            label: None,
+            ref_code: None,
+            circuits_dir: None,
+            amm_config_index: DEFAULT_AMM_CONFIG_INDEX,
+            slash_expired_orders: false,
+            tx_defaults: TxDefaults::default(),
+            lookup_table_ttl: DEFAULT_LOOKUP_TABLE_TTL,
+            extra_lookup_tables: vec![],
+            durable_nonce: None,
+        }
+    }
+}
+
+impl DarklakeSDKBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connect to the given RPC endpoint with the builder's commitment level
+    pub fn rpc_endpoint(mut self, rpc_endpoint: &str) -> Self {
+        self.rpc_endpoint = Some(rpc_endpoint.to_string());
+        self
+    }
+
+    /// Share an existing RPC client, its own commitment level is used for every request
+    pub fn rpc_client(mut self, rpc_client: Arc<RpcClient>) -> Self {
+        self.rpc_client = Some(rpc_client);
+        self
+    }
+
+    /// Send requests through a custom `RpcSender` (auth headers, timeouts, mocks) with the
+    /// builder's commitment level
+    pub fn rpc_sender<T: RpcSender + Send + Sync + 'static>(mut self, rpc_sender: T) -> Self {
+        self.rpc_sender = Some(Box::new(move |config| {
+            RpcClient::new_sender(rpc_sender, config)
+        }));
+        self
+    }
+
+    /// Read accounts, slots and blockhashes through a custom `ChainReader`, e.g. an
+    /// `InMemoryChainReader` to build transactions offline
+    pub fn chain_reader(mut self, chain_reader: Arc<dyn ChainReader>) -> Self {
+        self.chain_reader = Some(chain_reader);
+        self
+    }
+
+    /// Commitment level of the client created from `rpc_endpoint` or `rpc_sender`
+    pub fn commitment(mut self, commitment_level: CommitmentLevel) -> Self {
+        self.commitment_level = commitment_level;
+        self
+    }
+
+    /// The Darklake deployment to build instructions for
+    pub fn network(mut self, network: DarklakeNetworkConfig) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Application/user label, max 10 characters
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Referral code, max 20 characters
+    pub fn ref_code(mut self, ref_code: &str) -> Self {
+        self.ref_code = Some(ref_code.to_string());
+        self
+    }
+
+    /// Directory holding `settle.wasm`, `settle_final.zkey`, `settle.r1cs` and the matching
+    /// `cancel` files, instead of the circuits bundled with the crate
+    pub fn circuits_dir(mut self, circuits_dir: &str) -> Self {
+        self.circuits_dir = Some(circuits_dir.to_string());
+        self
+    }
+
+    /// Index of the AmmConfig (fee tier) pools are derived from
+    pub fn amm_config_index(mut self, amm_config_index: u32) -> Self {
+        self.amm_config_index = amm_config_index;
+        self
+    }
+
+    /// Slash the trader's expired order in `swap_tx` instead of failing
+    pub fn slash_expired_orders(mut self, slash_expired_orders: bool) -> Self {
+        self.slash_expired_orders = slash_expired_orders;
+        self
+    }
+
+    /// Compute budget of the transactions built by the `_tx` functions
+    pub fn tx_defaults(mut self, tx_defaults: TxDefaults) -> Self {
+        self.tx_defaults = tx_defaults;
+        self
+    }
+
+    /// How long fetched address lookup tables are reused, `Duration::ZERO` fetches them for every
+    /// transaction
+    pub fn lookup_table_ttl(mut self, lookup_table_ttl: Duration) -> Self {
+        self.lookup_table_ttl = lookup_table_ttl;
+        self
+    }
+
+    /// Lookup tables compiled into every transaction next to the network's, e.g. the caller's own
+    /// tables for instructions added to Darklake transactions
+    pub fn extra_lookup_tables(mut self, extra_lookup_tables: Vec<Pubkey>) -> Self {
+        self.extra_lookup_tables = extra_lookup_tables;
+        self
+    }
+
+    /// Build every `_tx` transaction with a durable nonce instead of a recent blockhash, same as
+    /// `set_durable_nonce`
+    pub fn durable_nonce(mut self, durable_nonce: DurableNonceConfig) -> Self {
+        self.durable_nonce = Some(durable_nonce);
+        self
+    }
+
+    /// Build the SDK
+    ///
+    /// # Returns
+    /// Returns an error when more than one RPC source is set, neither an RPC source nor a chain
+    /// reader is set, or the label or ref code is too long
+    pub fn build(mut self) -> Result<DarklakeSDK> {
+        let rpc_sources = [
+            self.rpc_client.is_some(),
+            self.rpc_sender.is_some(),
+            self.rpc_endpoint.is_some(),
+        ]
+        .into_iter()
+        .filter(|is_set| *is_set)
+        .count();
+
+        if rpc_sources > 1 {
+            bail!("Set only one of an RPC endpoint, client or sender");
+        }
+        if rpc_sources == 0 && self.chain_reader.is_none() {
+            bail!("An RPC endpoint, client, sender or chain reader is required");
+        }
+
+        if let Some(rpc_sender) = self.rpc_sender.take() {
+            self.rpc_client = Some(Arc::new(rpc_sender(RpcClientConfig::with_commitment(
+                CommitmentConfig {
+                    commitment: self.commitment_level,
+                },
+            ))));
+        }
+
+        DarklakeSDK::from_builder(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_reader::InMemoryChainReader;
+    use solana_rpc_client::mock_sender::MockSender;
+
+    #[test]
+    fn test_requires_exactly_one_rpc_source() {
+        assert!(DarklakeSDKBuilder::new().build().is_err());
+        assert!(
+            DarklakeSDKBuilder::new()
+                .rpc_endpoint("http://127.0.0.1:8899")
+                .rpc_sender(MockSender::new("succeeds".to_string()))
+                .build()
+                .is_err()
+        );
+        assert!(
+            DarklakeSDKBuilder::new()
+                .rpc_client(Arc::new(RpcClient::new_mock("succeeds".to_string())))
+                .rpc_sender(MockSender::new("succeeds".to_string()))
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_builds_with_shared_client_and_network() {
+        let rpc_client = Arc::new(RpcClient::new_mock("succeeds".to_string()));
+        let network = DarklakeNetworkConfig::devnet();
+
+        let sdk = DarklakeSDKBuilder::new()
+            .rpc_client(rpc_client)
+            .network(network.clone())
+            .amm_config_index(1)
+            .label("test")
+            .build()
+            .unwrap();
+
+        assert_eq!(*sdk.network(), network);
+        assert_eq!(sdk.amm_config(), network.amm_config_address(1));
+    }
+
+    #[test]
+    fn test_builds_offline_with_chain_reader() {
+        let sdk = DarklakeSDKBuilder::new()
+            .chain_reader(Arc::new(InMemoryChainReader::new()))
+            .build()
+            .unwrap();
+
+        assert_eq!(*sdk.network(), DarklakeNetworkConfig::mainnet());
+    }
+
+    #[test]
+    fn test_rejects_long_label() {
+        assert!(
+            DarklakeSDKBuilder::new()
+                .rpc_endpoint("http://127.0.0.1:8899")
+                .label("label-longer-than-ten")
+                .build()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,413 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use solana_account_decoder::UiAccountEncoding;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    account::Account,
+    address_lookup_table::{AddressLookupTableAccount, state::AddressLookupTable},
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+
+use crate::amm::AccountMap;
+
+/// Chain reads the SDK depends on
+///
+/// `RpcChainReader` is used unless another reader is passed to the builder. `InMemoryChainReader`
+/// serves accounts from memory so transactions can be built without a validator.
+#[async_trait]
+pub trait ChainReader: Send + Sync {
+    /// Account at the address, `None` if it does not exist
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>>;
+
+    /// Same as `get_account` at the given commitment, readers without commitment levels ignore it
+    async fn get_account_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        _commitment: CommitmentConfig,
+    ) -> Result<Option<Account>> {
+        self.get_account(pubkey).await
+    }
+
+    /// Accounts at the addresses, in the same order
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let mut accounts = Vec::with_capacity(pubkeys.len());
+        for pubkey in pubkeys {
+            accounts.push(self.get_account(pubkey).await?);
+        }
+
+        Ok(accounts)
+    }
+
+    /// Accounts owned by the program whose data starts with the discriminator
+    async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        discriminator: &[u8],
+    ) -> Result<Vec<(Pubkey, Account)>>;
+
+    /// The latest processed slot
+    async fn get_slot(&self) -> Result<u64>;
+
+    /// The current epoch
+    async fn get_epoch(&self) -> Result<u64>;
+
+    async fn get_latest_blockhash(&self) -> Result<Hash>;
+
+    async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64>;
+
+    /// Address lookup table at the address, deserialized for message compilation
+    async fn get_address_lookup_table(&self, pubkey: &Pubkey) -> Result<AddressLookupTableAccount> {
+        let alt_account = self
+            .get_account(pubkey)
+            .await?
+            .with_context(|| format!("Address lookup table {} not found", pubkey))?;
+
+        let table = AddressLookupTable::deserialize(&alt_account.data)?;
+
+        Ok(AddressLookupTableAccount {
+            key: *pubkey,
+            addresses: table.addresses.to_vec(),
+        })
+    }
+}
+
+/// `ChainReader` backed by an RPC client
+pub struct RpcChainReader {
+    rpc_client: Arc<RpcClient>,
+}
+
+impl RpcChainReader {
+    pub fn new(rpc_client: Arc<RpcClient>) -> Self {
+        Self { rpc_client }
+    }
+}
+
+#[async_trait]
+impl ChainReader for RpcChainReader {
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>> {
+        Ok(self
+            .rpc_client
+            .get_account_with_commitment(pubkey, self.rpc_client.commitment())
+            .await?
+            .value)
+    }
+
+    async fn get_account_with_commitment(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> Result<Option<Account>> {
+        Ok(self
+            .rpc_client
+            .get_account_with_commitment(pubkey, commitment)
+            .await?
+            .value)
+    }
+
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        Ok(self.rpc_client.get_multiple_accounts(pubkeys).await?)
+    }
+
+    async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        discriminator: &[u8],
+    ) -> Result<Vec<(Pubkey, Account)>> {
+        Ok(self
+            .rpc_client
+            .get_program_accounts_with_config(
+                program_id,
+                RpcProgramAccountsConfig {
+                    filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                        0,
+                        discriminator.to_vec(),
+                    ))]),
+                    account_config: RpcAccountInfoConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        ..RpcAccountInfoConfig::default()
+                    },
+                    ..RpcProgramAccountsConfig::default()
+                },
+            )
+            .await?)
+    }
+
+    async fn get_slot(&self) -> Result<u64> {
+        Ok(self
+            .rpc_client
+            .get_slot_with_commitment(CommitmentConfig::processed())
+            .await?)
+    }
+
+    async fn get_epoch(&self) -> Result<u64> {
+        Ok(self.rpc_client.get_epoch_info().await?.epoch)
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash> {
+        Ok(self.rpc_client.get_latest_blockhash().await?)
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64> {
+        Ok(self
+            .rpc_client
+            .get_minimum_balance_for_rent_exemption(data_len)
+            .await?)
+    }
+}
+
+#[derive(Default)]
+struct InMemoryChainState {
+    accounts: HashMap<Pubkey, Account>,
+    slot: u64,
+    epoch: u64,
+    blockhash: Hash,
+}
+
+/// `ChainReader` serving accounts from memory, for offline tests
+///
+/// Seed it with `set_account` or from JSON fixtures in the format written by
+/// `solana account <ADDRESS> --output json`. Slot, epoch and blockhash default to zero and can be
+/// changed at any time, also after the reader was handed to the SDK.
+#[derive(Default)]
+pub struct InMemoryChainReader {
+    state: RwLock<InMemoryChainState>,
+}
+
+impl InMemoryChainReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every `.json` account fixture in a directory
+    pub fn from_fixture_dir(fixture_dir: impl AsRef<Path>) -> Result<Self> {
+        let chain_reader = Self::new();
+
+        for entry in fs::read_dir(fixture_dir.as_ref())
+            .with_context(|| format!("Failed to read {}", fixture_dir.as_ref().display()))?
+        {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                chain_reader.load_fixture(&path)?;
+            }
+        }
+
+        Ok(chain_reader)
+    }
+
+    /// Load a single account fixture
+    ///
+    /// # Returns
+    /// Returns the address of the loaded account
+    pub fn load_fixture(&self, fixture_path: impl AsRef<Path>) -> Result<Pubkey> {
+        let fixture = fs::read_to_string(fixture_path.as_ref())
+            .with_context(|| format!("Failed to read {}", fixture_path.as_ref().display()))?;
+        let (pubkey, account) = parse_account_fixture(&fixture)
+            .with_context(|| format!("Invalid fixture {}", fixture_path.as_ref().display()))?;
+
+        self.set_account(pubkey, account);
+
+        Ok(pubkey)
+    }
+
+    pub fn set_account(&self, pubkey: Pubkey, account: Account) {
+        self.state.write().unwrap().accounts.insert(pubkey, account);
+    }
+
+    /// Insert every account of the map as a rent-exempt account, e.g. the map used to build a
+    /// `DarklakePool`
+    pub fn set_account_map(&self, account_map: &AccountMap) {
+        let mut state = self.state.write().unwrap();
+        for (pubkey, account_data) in account_map {
+            state.accounts.insert(
+                *pubkey,
+                Account {
+                    lamports: Rent::default().minimum_balance(account_data.data.len()),
+                    data: account_data.data.clone(),
+                    owner: account_data.owner,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            );
+        }
+    }
+
+    pub fn remove_account(&self, pubkey: &Pubkey) {
+        self.state.write().unwrap().accounts.remove(pubkey);
+    }
+
+    pub fn set_slot(&self, slot: u64) {
+        self.state.write().unwrap().slot = slot;
+    }
+
+    pub fn set_epoch(&self, epoch: u64) {
+        self.state.write().unwrap().epoch = epoch;
+    }
+
+    pub fn set_blockhash(&self, blockhash: Hash) {
+        self.state.write().unwrap().blockhash = blockhash;
+    }
+}
+
+#[async_trait]
+impl ChainReader for InMemoryChainReader {
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.state.read().unwrap().accounts.get(pubkey).cloned())
+    }
+
+    async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        discriminator: &[u8],
+    ) -> Result<Vec<(Pubkey, Account)>> {
+        Ok(self
+            .state
+            .read()
+            .unwrap()
+            .accounts
+            .iter()
+            .filter(|(_, account)| {
+                account.owner == *program_id && account.data.starts_with(discriminator)
+            })
+            .map(|(pubkey, account)| (*pubkey, account.clone()))
+            .collect())
+    }
+
+    async fn get_slot(&self) -> Result<u64> {
+        Ok(self.state.read().unwrap().slot)
+    }
+
+    async fn get_epoch(&self) -> Result<u64> {
+        Ok(self.state.read().unwrap().epoch)
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<Hash> {
+        Ok(self.state.read().unwrap().blockhash)
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64> {
+        Ok(Rent::default().minimum_balance(data_len))
+    }
+}
+
+/// Parse an account written by `solana account <ADDRESS> --output json`
+fn parse_account_fixture(fixture: &str) -> Result<(Pubkey, Account)> {
+    let fixture: serde_json::Value = serde_json::from_str(fixture)?;
+
+    let pubkey = Pubkey::from_str(fixture["pubkey"].as_str().context("Missing pubkey")?)?;
+    let account = &fixture["account"];
+
+    let data = match account["data"].as_array().map(Vec::as_slice) {
+        Some([data, encoding]) if encoding == "base64" => {
+            STANDARD.decode(data.as_str().context("Invalid account data")?)?
+        }
+        _ => bail!("Account data must be a [data, \"base64\"] pair"),
+    };
+
+    Ok((
+        pubkey,
+        Account {
+            lamports: account["lamports"].as_u64().context("Missing lamports")?,
+            data,
+            owner: Pubkey::from_str(account["owner"].as_str().context("Missing owner")?)?,
+            executable: account["executable"].as_bool().unwrap_or(false),
+            rent_epoch: account["rentEpoch"].as_u64().unwrap_or(0),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::lookup_table_account;
+
+    #[test]
+    fn test_parse_account_fixture() {
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let fixture = serde_json::json!({
+            "pubkey": pubkey.to_string(),
+            "account": {
+                "lamports": 1_461_600,
+                "data": [STANDARD.encode([1, 2, 3]), "base64"],
+                "owner": owner.to_string(),
+                "executable": false,
+                "rentEpoch": u64::MAX,
+                "space": 3,
+            },
+        });
+
+        let (parsed_pubkey, account) = parse_account_fixture(&fixture.to_string()).unwrap();
+        assert_eq!(parsed_pubkey, pubkey);
+        assert_eq!(account.data, vec![1, 2, 3]);
+        assert_eq!(account.owner, owner);
+        assert_eq!(account.rent_epoch, u64::MAX);
+
+        let fixture = serde_json::json!({
+            "pubkey": pubkey.to_string(),
+            "account": { "lamports": 1, "data": ["010203", "hex"], "owner": owner.to_string() },
+        });
+        assert!(parse_account_fixture(&fixture.to_string()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_program_accounts_and_lookup_table() {
+        let chain_reader = InMemoryChainReader::new();
+        let program_id = Pubkey::new_unique();
+        let (pool, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        for (pubkey, data) in [(pool, vec![1, 2, 3]), (other, vec![4, 5, 6])] {
+            chain_reader.set_account(
+                pubkey,
+                Account {
+                    lamports: 1,
+                    data,
+                    owner: program_id,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            );
+        }
+
+        let program_accounts = chain_reader
+            .get_program_accounts(&program_id, &[1, 2])
+            .await
+            .unwrap();
+        assert_eq!(program_accounts.len(), 1);
+        assert_eq!(program_accounts[0].0, pool);
+
+        let alt = Pubkey::new_unique();
+        let addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        chain_reader.set_account_map(&AccountMap::from([(
+            alt,
+            lookup_table_account(addresses.clone()),
+        )]));
+
+        let alt_account = chain_reader.get_address_lookup_table(&alt).await.unwrap();
+        assert_eq!(alt_account.addresses, addresses);
+        assert!(
+            chain_reader
+                .get_address_lookup_table(&Pubkey::new_unique())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -5,12 +5,21 @@ use solana_sdk::pubkey;
 pub const DARKLAKE_PROGRAM_ID: Pubkey = pubkey!("darkr3FB87qAZmgLwKov6Hk9Yiah5UT4rUYu8Zhthw1");
 
 pub const SOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111111");
+pub const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+pub const DEVNET_USDC_MINT: Pubkey = pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
 pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 pub const DEVNET_CREATE_POOL_FEE_VAULT: Pubkey =
     pubkey!("6vUjEKC5mkiDMdMhkxV8SYzPQAk39aPKbjGataVnkUss");
 pub const MAINNET_CREATE_POOL_FEE_VAULT: Pubkey =
     pubkey!("HNQdnRgtnsgcx7E836nZ1JwrQstWBEJMnRVy8doY366A");
 
+// Fee rates are expressed in millionths, 1_000_000 = 100%
+pub const MAX_PERCENTAGE: u64 = 1_000_000;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// AmmConfig (fee tier) used unless another index is configured
+pub const DEFAULT_AMM_CONFIG_INDEX: u32 = 0;
+
 pub const POOL_SEED: &[u8] = b"pool";
 pub const AMM_CONFIG_SEED: &[u8] = b"amm_config";
 pub const AUTHORITY_SEED: &[u8] = b"authority";
@@ -21,12 +30,36 @@ pub const ORDER_WSOL_SEED: &[u8] = b"order_wsol";
 pub const METADATA_SEED: &[u8] = b"metadata";
 pub const POOL_RESERVE_SEED: &[u8] = b"pool_reserve";
 
+// Anchor account discriminators
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+pub const AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+pub const ORDER_DISCRIMINATOR: [u8; 8] = [134, 173, 223, 185, 77, 86, 28, 51];
+
+// Anchor instruction discriminators
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+pub const SETTLE_DISCRIMINATOR: [u8; 8] = [175, 42, 185, 87, 144, 131, 102, 212];
+pub const CANCEL_DISCRIMINATOR: [u8; 8] = [232, 219, 223, 41, 219, 236, 220, 190];
+pub const SLASH_DISCRIMINATOR: [u8; 8] = [204, 141, 18, 161, 8, 177, 92, 142];
+pub const ADD_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [181, 157, 89, 67, 143, 182, 52, 72];
+pub const REMOVE_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [80, 85, 209, 72, 24, 206, 177, 108];
+pub const INITIALIZE_POOL_DISCRIMINATOR: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
+
+// Anchor event discriminators
+pub const SWAP_EVENT_DISCRIMINATOR: [u8; 8] = [64, 198, 205, 232, 38, 8, 113, 226];
+pub const SETTLE_EVENT_DISCRIMINATOR: [u8; 8] = [14, 166, 206, 248, 35, 1, 134, 48];
+pub const CANCEL_EVENT_DISCRIMINATOR: [u8; 8] = [71, 137, 239, 100, 220, 3, 242, 47];
+pub const SLASH_EVENT_DISCRIMINATOR: [u8; 8] = [126, 150, 240, 12, 183, 103, 116, 71];
+pub const ADD_LIQUIDITY_EVENT_DISCRIMINATOR: [u8; 8] = [27, 178, 153, 186, 47, 196, 140, 45];
+pub const REMOVE_LIQUIDITY_EVENT_DISCRIMINATOR: [u8; 8] = [141, 199, 182, 123, 159, 94, 215, 102];
+pub const INITIALIZE_POOL_EVENT_DISCRIMINATOR: [u8; 8] = [114, 34, 145, 114, 237, 44, 216, 235];
+
 pub const DEVNET_LOOKUP: Pubkey = pubkey!("fUT5cRYT7RTS4kSq7ZpPwqaH7E68soubbutFxYHeNjo");
 pub const MAINNET_LOOKUP: Pubkey = pubkey!("2h3Sz2G84TcrqWc3FAyRZjjf5aCExMKM5sG3fh1bBXSg");
 
+// Mainnet PDAs, other deployments derive them from their `DarklakeNetworkConfig`
 lazy_static! {
     pub static ref AMM_CONFIG: Pubkey = Pubkey::find_program_address(
-        &[AMM_CONFIG_SEED, &0u32.to_le_bytes()],
+        &[AMM_CONFIG_SEED, &DEFAULT_AMM_CONFIG_INDEX.to_le_bytes()],
         &DARKLAKE_PROGRAM_ID,
     )
     .0;
@@ -4,14 +4,19 @@ use solana_sdk::sysvar::SysvarId;
 
 use crate::account_metas::DarklakeAmmInitializePool;
 use crate::constants::{
-    AMM_CONFIG, AUTHORITY, DARKLAKE_PROGRAM_ID, DEVNET_CREATE_POOL_FEE_VAULT, LIQUIDITY_SEED,
-    MAINNET_CREATE_POOL_FEE_VAULT, METADATA_PROGRAM_ID, METADATA_SEED, ORDER_SEED, ORDER_WSOL_SEED,
-    POOL_RESERVE_SEED, POOL_SEED, POOL_WSOL_RESERVE_SEED,
+    ADD_LIQUIDITY_DISCRIMINATOR, AMM_CONFIG_DISCRIMINATOR, BPS_DENOMINATOR, CANCEL_DISCRIMINATOR,
+    DARKLAKE_PROGRAM_ID, INITIALIZE_POOL_DISCRIMINATOR, LIQUIDITY_SEED, MAX_PERCENTAGE,
+    METADATA_SEED, ORDER_DISCRIMINATOR, ORDER_SEED, ORDER_WSOL_SEED, POOL_DISCRIMINATOR,
+    POOL_RESERVE_SEED, POOL_SEED, POOL_WSOL_RESERVE_SEED, REMOVE_LIQUIDITY_DISCRIMINATOR,
+    SETTLE_DISCRIMINATOR, SLASH_DISCRIMINATOR, SWAP_DISCRIMINATOR,
 };
+use crate::error::{AccountDecodeError, QuoteError};
+use crate::network::DarklakeNetworkConfig;
 use crate::proof::proof_generator::to_32_byte_buffer;
 use crate::proof::utils::{
     bytes_to_bigint, compute_poseidon_hash_with_salt, u64_array_to_u8_array_le,
 };
+use crate::token_extensions::{MintExtensions, TokenTransfer, get_transfer_fee_config};
 use crate::utils::get_transfer_fee;
 use crate::{
     account_metas::{
@@ -23,12 +28,10 @@ use crate::{
 
 use anchor_lang::{AnchorDeserialize, AnchorSerialize, system_program};
 use anyhow::{Context, Result, bail};
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, prelude::ToPrimitive};
 use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
 use spl_token::{native_mint, state::Account as SplTokenAccount};
-use spl_token_2022::extension::{
-    BaseStateWithExtensions, StateWithExtensions, transfer_fee::TransferFeeConfig,
-};
+use spl_token_2022::extension::{StateWithExtensions, transfer_fee::TransferFeeConfig};
 
 #[derive(Clone)]
 pub(crate) struct DarklakeAmm {
@@ -41,9 +44,13 @@ pub(crate) struct DarklakeAmm {
     pub token_y_owner: Pubkey,
     pub token_x_transfer_fee_config: Option<TransferFeeConfig>,
     pub token_y_transfer_fee_config: Option<TransferFeeConfig>,
+    pub token_x_extensions: MintExtensions,
+    pub token_y_extensions: MintExtensions,
+    pub network: DarklakeNetworkConfig,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AmmConfig {
     pub trade_fee_rate: u64,
     pub create_pool_fee: u64,
@@ -61,10 +68,14 @@ pub struct AmmConfig {
     pub padding: [u64; 16],
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Order {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub trader: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y: Pubkey,
 
     pub actual_in: u64,
@@ -77,6 +88,7 @@ pub struct Order {
     pub protocol_fee: u64,
     pub wsol_deposit: u64,
 
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub c_min: [u8; 32],
 
     pub is_x_to_y: bool,
@@ -87,15 +99,22 @@ pub struct Order {
     pub padding: [u64; 3],
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pool {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub creator: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub amm_config: Pubkey,
 
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_mint_y: Pubkey,
 
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub reserve_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub reserve_y: Pubkey,
 
     pub token_lp_supply: u64,
@@ -116,36 +135,135 @@ pub struct Pool {
     pub padding: [u64; 2],
 }
 
+impl AmmConfig {
+    /// Account size without the 8-byte discriminator
+    pub const LEN: usize = 8 * 6 + 1 + 1 + 8 * 16;
+
+    /// Decode an `AmmConfig` account, checking its owner, discriminator and length
+    pub fn try_from_account_data(
+        data: &[u8],
+        owner: &Pubkey,
+    ) -> std::result::Result<Self, AccountDecodeError> {
+        Self::try_from_program_account_data(data, owner, &DARKLAKE_PROGRAM_ID)
+    }
+
+    /// Decode an `AmmConfig` account owned by a custom deployment of the Darklake program
+    pub fn try_from_program_account_data(
+        data: &[u8],
+        owner: &Pubkey,
+        program_id: &Pubkey,
+    ) -> std::result::Result<Self, AccountDecodeError> {
+        decode_account(
+            data,
+            owner,
+            program_id,
+            &AMM_CONFIG_DISCRIMINATOR,
+            Self::LEN,
+        )
+    }
+}
+
+impl Order {
+    /// Account size without the 8-byte discriminator
+    pub const LEN: usize = 32 * 3 + 8 * 9 + 32 + 1 + 1 + 8 + 8 * 3;
+
+    /// Decode an `Order` account, checking its owner, discriminator and length
+    pub fn try_from_account_data(
+        data: &[u8],
+        owner: &Pubkey,
+    ) -> std::result::Result<Self, AccountDecodeError> {
+        Self::try_from_program_account_data(data, owner, &DARKLAKE_PROGRAM_ID)
+    }
+
+    /// Decode an `Order` account owned by a custom deployment of the Darklake program
+    pub fn try_from_program_account_data(
+        data: &[u8],
+        owner: &Pubkey,
+        program_id: &Pubkey,
+    ) -> std::result::Result<Self, AccountDecodeError> {
+        decode_account(data, owner, program_id, &ORDER_DISCRIMINATOR, Self::LEN)
+    }
+}
+
+impl Pool {
+    /// Account size without the 8-byte discriminator
+    pub const LEN: usize = 32 * 6 + 8 * 3 + 8 * 2 + 8 * 2 + 1 + 8 * 2 + 8 * 2;
+
+    /// Decode a `Pool` account, checking its owner, discriminator and length
+    pub fn try_from_account_data(
+        data: &[u8],
+        owner: &Pubkey,
+    ) -> std::result::Result<Self, AccountDecodeError> {
+        Self::try_from_program_account_data(data, owner, &DARKLAKE_PROGRAM_ID)
+    }
+
+    /// Decode a `Pool` account owned by a custom deployment of the Darklake program
+    pub fn try_from_program_account_data(
+        data: &[u8],
+        owner: &Pubkey,
+        program_id: &Pubkey,
+    ) -> std::result::Result<Self, AccountDecodeError> {
+        decode_account(data, owner, program_id, &POOL_DISCRIMINATOR, Self::LEN)
+    }
+}
+
+fn decode_account<T: AnchorDeserialize>(
+    data: &[u8],
+    owner: &Pubkey,
+    program_id: &Pubkey,
+    discriminator: &[u8; 8],
+    len: usize,
+) -> std::result::Result<T, AccountDecodeError> {
+    if owner != program_id {
+        return Err(AccountDecodeError::InvalidOwner {
+            expected: *program_id,
+            actual: *owner,
+        });
+    }
+
+    if data.len() < 8 + len {
+        return Err(AccountDecodeError::AccountDataTooShort {
+            expected: 8 + len,
+            actual: data.len(),
+        });
+    }
+
+    if data[..8] != discriminator[..] {
+        return Err(AccountDecodeError::InvalidDiscriminator {
+            expected: *discriminator,
+            actual: data[..8].to_vec(),
+        });
+    }
+
+    T::deserialize(&mut &data[8..]).map_err(|e| AccountDecodeError::Deserialize(e.to_string()))
+}
+
+/// Share of a reserve owned by an amount of LP tokens
+fn lp_to_token_amount(amount_lp: u64, reserve: u64, lp_supply: u64, round_up: bool) -> Result<u64> {
+    if lp_supply == 0 {
+        return Ok(0);
+    }
+
+    let numerator = amount_lp as u128 * reserve as u128;
+    let amount = if round_up {
+        numerator.div_ceil(lp_supply as u128)
+    } else {
+        numerator / lp_supply as u128
+    };
+
+    u64::try_from(amount).context("Token amount overflow")
+}
+
 impl Amm for DarklakeAmm {
     fn load_pool(pool: &KeyedAccount) -> Result<Self>
     where
         Self: Sized,
     {
-        Ok(DarklakeAmm {
-            key: pool.key,
-            pool: Pool::deserialize(&mut &pool.account.data[8..])?,
-            amm_config: AmmConfig {
-                trade_fee_rate: 0,
-                create_pool_fee: 0,
-                protocol_fee_rate: 0,
-                wsol_trade_deposit: 0,
-                deadline_slot_duration: 0,
-                ratio_change_tolerance_rate: 0,
-                bump: 0,
-                halted: false,
-                padding: [0; 16],
-            },
-            reserve_x_balance: 0,
-            reserve_y_balance: 0,
-            token_x_owner: Pubkey::default(),
-            token_y_owner: Pubkey::default(),
-            token_x_transfer_fee_config: None,
-            token_y_transfer_fee_config: None,
-        })
+        Self::load_pool_with_network(pool, DarklakeNetworkConfig::mainnet())
     }
 
     fn program_id(&self) -> Pubkey {
-        DARKLAKE_PROGRAM_ID
+        self.network.program_id
     }
 
     fn key(&self) -> Pubkey {
@@ -172,10 +290,19 @@ impl Amm for DarklakeAmm {
             .get(&self.key)
             .context("Darklake pool account not found")?;
 
-        self.pool = Pool::deserialize(&mut &account.data[8..])?;
+        self.pool = Pool::try_from_program_account_data(
+            &account.data,
+            &account.owner,
+            &self.network.program_id,
+        )?;
 
-        let amm_config_data = try_get_account_data(account_map, &self.pool.amm_config)?;
-        let amm_config = AmmConfig::deserialize(&mut &amm_config_data[8..])?;
+        let (amm_config_data, amm_config_owner) =
+            try_get_account_data_and_owner(account_map, &self.pool.amm_config)?;
+        let amm_config = AmmConfig::try_from_program_account_data(
+            amm_config_data,
+            amm_config_owner,
+            &self.network.program_id,
+        )?;
 
         self.amm_config = amm_config;
 
@@ -189,17 +316,29 @@ impl Amm for DarklakeAmm {
         self.reserve_y_balance =
             Self::parse_token_account_balance(&token_y_data, &token_y_owner, &self.pool.reserve_y)?;
 
-        self.token_x_transfer_fee_config = self
-            .get_transfer_fee_config(&token_x_data, token_x_owner)
-            .unwrap_or(None);
-
-        self.token_y_transfer_fee_config = self
-            .get_transfer_fee_config(&token_y_data, token_y_owner)
-            .unwrap_or(None);
-
         self.token_x_owner = *token_x_owner;
         self.token_y_owner = *token_y_owner;
 
+        let (mint_x_data, mint_x_owner) =
+            try_get_account_data_and_owner(account_map, &self.pool.token_mint_x)?;
+        let (mint_y_data, mint_y_owner) =
+            try_get_account_data_and_owner(account_map, &self.pool.token_mint_y)?;
+
+        self.token_x_transfer_fee_config = get_transfer_fee_config(mint_x_data, mint_x_owner)
+            .with_context(|| format!("Invalid mint {}", self.pool.token_mint_x))?;
+        self.token_y_transfer_fee_config = get_transfer_fee_config(mint_y_data, mint_y_owner)
+            .with_context(|| format!("Invalid mint {}", self.pool.token_mint_y))?;
+
+        self.token_x_extensions =
+            MintExtensions::from_mint_account_data(mint_x_data, mint_x_owner)?;
+        self.token_y_extensions =
+            MintExtensions::from_mint_account_data(mint_y_data, mint_y_owner)?;
+
+        self.token_x_extensions
+            .check_supported(&self.pool.token_mint_x)?;
+        self.token_y_extensions
+            .check_supported(&self.pool.token_mint_y)?;
+
         Ok(())
     }
 
@@ -270,9 +409,38 @@ impl Amm for DarklakeAmm {
         let actual_output_amount = result.to_amount.checked_sub(output_transfer_fee).unwrap();
 
         if actual_output_amount == 0 {
-            bail!("Output is zero");
+            return Err(QuoteError::OutputIsZero.into());
         }
 
+        let (available_x, available_y) = self.get_available_reserves();
+        let (reserve_in, reserve_out) = if is_swap_x_to_y {
+            (available_x, available_y)
+        } else {
+            (available_y, available_x)
+        };
+
+        let spot_price = if reserve_in == 0 {
+            Decimal::ZERO
+        } else {
+            Decimal::from(reserve_out) / Decimal::from(reserve_in)
+        };
+        let execution_price =
+            Decimal::from(actual_output_amount) / Decimal::from(quote_params.amount);
+
+        // the impact of the curve alone: output before the output transfer fee over the input left
+        // after the input transfer fee and the trade fee
+        let curve_in = exchange_in.saturating_sub(result.trade_fee);
+        let price_impact_bps = if spot_price.is_zero() || curve_in == 0 {
+            0
+        } else {
+            let curve_price = Decimal::from(result.to_amount) / Decimal::from(curve_in);
+            ((spot_price - curve_price) / spot_price * Decimal::from(BPS_DENOMINATOR))
+                .max(Decimal::ZERO)
+                .round()
+                .to_u64()
+                .unwrap_or(0)
+        };
+
         Ok(Quote {
             in_amount: result.from_amount,
             out_amount: actual_output_amount,
@@ -282,7 +450,14 @@ impl Amm for DarklakeAmm {
             } else {
                 self.pool.token_mint_y
             },
-            fee_pct: Decimal::from(self.amm_config.trade_fee_rate),
+            fee_pct: Decimal::from(self.amm_config.trade_fee_rate) * Decimal::ONE_HUNDRED
+                / Decimal::from(MAX_PERCENTAGE),
+            protocol_fee: result.protocol_fee,
+            input_transfer_fee,
+            output_transfer_fee,
+            spot_price,
+            execution_price,
+            price_impact_bps,
         })
     }
 
@@ -295,7 +470,7 @@ impl Amm for DarklakeAmm {
             ..
         } = swap_params;
 
-        let authority = AUTHORITY.key();
+        let authority = self.network.authority();
         let is_swap_x_to_y = swap_params.source_mint == self.pool.token_mint_x;
 
         let user_token_account_wsol = DarklakeAmm::get_user_token_account(
@@ -314,13 +489,14 @@ impl Amm for DarklakeAmm {
             self.token_y_owner,
         );
 
-        let pool_wsol_reserve = DarklakeAmm::get_pool_wsol_reserve(self.key);
+        let pool_wsol_reserve =
+            DarklakeAmm::get_pool_wsol_reserve(&self.network.program_id, self.key);
         let order = self.get_order(token_transfer_authority);
 
         let commitment = to_32_byte_buffer(&bytes_to_bigint(&u64_array_to_u8_array_le(
             &compute_poseidon_hash_with_salt(*min_out, *salt),
         )));
-        let discriminator = [248, 198, 158, 145, 225, 117, 135, 200];
+        let discriminator = SWAP_DISCRIMINATOR;
 
         let mut data = discriminator.to_vec();
 
@@ -379,16 +555,6 @@ impl Amm for DarklakeAmm {
         Ok(self.get_order(user))
     }
 
-    fn parse_order_data(&self, order_data: &[u8]) -> Result<Order> {
-        let order = Order::deserialize(&mut &order_data[8..])?;
-        Ok(order)
-    }
-
-    fn is_order_expired(&self, order_data: &[u8], current_slot: u64) -> Result<bool> {
-        let order = Order::deserialize(&mut &order_data[8..])?;
-        Ok(order.deadline < current_slot)
-    }
-
     fn get_settle_and_account_metas(
         &self,
         settle_params: &SettleParams,
@@ -412,9 +578,10 @@ impl Amm for DarklakeAmm {
             bail!("Order has expired");
         }
 
-        let authority = AUTHORITY.key();
+        let authority = self.network.authority();
 
-        let pool_wsol_reserve = DarklakeAmm::get_pool_wsol_reserve(self.key);
+        let pool_wsol_reserve =
+            DarklakeAmm::get_pool_wsol_reserve(&self.network.program_id, self.key);
 
         let user_token_account_wsol =
             DarklakeAmm::get_user_token_account(*order_owner, native_mint::ID, spl_token::ID);
@@ -439,7 +606,7 @@ impl Amm for DarklakeAmm {
         if !is_settle {
             bail!("Cant settle this order, min_out > output");
         }
-        let discriminator = [175, 42, 185, 87, 144, 131, 102, 212];
+        let discriminator = SETTLE_DISCRIMINATOR;
 
         let mut data = discriminator.to_vec();
         data.extend_from_slice(&proof_params.generated_proof.proof_a);
@@ -514,9 +681,10 @@ impl Amm for DarklakeAmm {
             bail!("Order has expired");
         }
 
-        let authority = AUTHORITY.key();
+        let authority = self.network.authority();
 
-        let pool_wsol_reserve = DarklakeAmm::get_pool_wsol_reserve(self.key);
+        let pool_wsol_reserve =
+            DarklakeAmm::get_pool_wsol_reserve(&self.network.program_id, self.key);
 
         let user_token_account_wsol =
             DarklakeAmm::get_user_token_account(*order_owner, native_mint::ID, spl_token::ID);
@@ -541,7 +709,7 @@ impl Amm for DarklakeAmm {
             bail!("Cant cancel this order, min_out <= output");
         }
 
-        let discriminator = [232, 219, 223, 41, 219, 236, 220, 190];
+        let discriminator = CANCEL_DISCRIMINATOR;
 
         let mut data = discriminator.to_vec();
         data.extend_from_slice(&proof_params.generated_proof.proof_a);
@@ -605,9 +773,10 @@ impl Amm for DarklakeAmm {
             bail!("Order has NOT expired");
         }
 
-        let authority = AUTHORITY.key();
+        let authority = self.network.authority();
 
-        let pool_wsol_reserve = DarklakeAmm::get_pool_wsol_reserve(self.key);
+        let pool_wsol_reserve =
+            DarklakeAmm::get_pool_wsol_reserve(&self.network.program_id, self.key);
 
         let user_token_account_x = DarklakeAmm::get_user_token_account(
             *order_owner,
@@ -624,7 +793,7 @@ impl Amm for DarklakeAmm {
             DarklakeAmm::get_user_token_account(*settle_signer, native_mint::ID, spl_token::ID);
         let order = self.get_order(order_owner);
 
-        let discriminator = [204, 141, 18, 161, 8, 177, 92, 142];
+        let discriminator = SLASH_DISCRIMINATOR;
 
         let mut data = discriminator.to_vec();
         let serialized_label = label.try_to_vec()?;
@@ -673,19 +842,19 @@ impl Amm for DarklakeAmm {
             label,
         } = add_liquidity_params;
 
-        let authority = AUTHORITY.key();
+        let authority = self.network.authority();
 
         let user_token_account_x =
             DarklakeAmm::get_user_token_account(*user, self.pool.token_mint_x, self.token_x_owner);
         let user_token_account_y =
             DarklakeAmm::get_user_token_account(*user, self.pool.token_mint_y, self.token_y_owner);
 
-        let token_mint_lp = DarklakeAmm::get_token_mint_lp(self.key);
+        let token_mint_lp = DarklakeAmm::get_token_mint_lp(&self.network.program_id, self.key);
 
         let user_token_account_lp =
             DarklakeAmm::get_user_token_account(*user, token_mint_lp, spl_token::ID);
 
-        let discriminator = [181, 157, 89, 67, 143, 182, 52, 72];
+        let discriminator = ADD_LIQUIDITY_DISCRIMINATOR;
 
         let mut data = discriminator.to_vec();
         data.extend_from_slice(&amount_lp.to_le_bytes());
@@ -741,19 +910,19 @@ impl Amm for DarklakeAmm {
             label,
         } = remove_liquidity_params;
 
-        let authority = AUTHORITY.key();
+        let authority = self.network.authority();
 
         let user_token_account_x =
             DarklakeAmm::get_user_token_account(*user, self.pool.token_mint_x, self.token_x_owner);
         let user_token_account_y =
             DarklakeAmm::get_user_token_account(*user, self.pool.token_mint_y, self.token_y_owner);
 
-        let token_mint_lp = DarklakeAmm::get_token_mint_lp(self.key);
+        let token_mint_lp = DarklakeAmm::get_token_mint_lp(&self.network.program_id, self.key);
 
         let user_token_account_lp =
             DarklakeAmm::get_user_token_account(*user, token_mint_lp, spl_token::ID);
 
-        let discriminator = [80, 85, 209, 72, 24, 206, 177, 108];
+        let discriminator = REMOVE_LIQUIDITY_DISCRIMINATOR;
 
         let mut data = discriminator.to_vec();
         data.extend_from_slice(&amount_lp.to_le_bytes());
@@ -797,10 +966,10 @@ impl Amm for DarklakeAmm {
     fn get_initialize_pool_and_account_metas(
         &self,
         initialize_pool_params: &InitializePoolParams,
-        is_devnet: bool,
     ) -> Result<InitializePoolAndAccountMetas> {
         let InitializePoolParams {
             user,
+            amm_config,
             token_x,
             token_x_program,
             token_y,
@@ -810,24 +979,28 @@ impl Amm for DarklakeAmm {
             label,
         } = initialize_pool_params;
 
-        let authority = AUTHORITY.key();
-        let pool_address = DarklakeAmm::get_pool_address(token_x, token_y);
+        let authority = self.network.authority();
+        let pool_address =
+            DarklakeAmm::get_pool_address(&self.network.program_id, amm_config, token_x, token_y);
 
         let user_token_account_x =
             DarklakeAmm::get_user_token_account(*user, *token_x, *token_x_program);
         let user_token_account_y =
             DarklakeAmm::get_user_token_account(*user, *token_y, *token_y_program);
 
-        let token_mint_lp = DarklakeAmm::get_token_mint_lp(pool_address);
+        let token_mint_lp = DarklakeAmm::get_token_mint_lp(&self.network.program_id, pool_address);
 
-        let metadata_account = DarklakeAmm::get_token_metadata(token_mint_lp);
-        let metadata_account_x = DarklakeAmm::get_token_metadata(*token_x);
-        let metadata_account_y = DarklakeAmm::get_token_metadata(*token_y);
+        let metadata_account =
+            DarklakeAmm::get_token_metadata(&self.network.metadata_program_id, token_mint_lp);
+        let metadata_account_x =
+            DarklakeAmm::get_token_metadata(&self.network.metadata_program_id, *token_x);
+        let metadata_account_y =
+            DarklakeAmm::get_token_metadata(&self.network.metadata_program_id, *token_y);
 
         let user_token_account_lp =
             DarklakeAmm::get_user_token_account(*user, token_mint_lp, spl_token::ID);
 
-        let discriminator = [95, 180, 10, 172, 84, 174, 232, 40];
+        let discriminator = INITIALIZE_POOL_DISCRIMINATOR;
 
         let mut data = discriminator.to_vec();
         data.extend_from_slice(&amount_x.to_le_bytes());
@@ -847,7 +1020,7 @@ impl Amm for DarklakeAmm {
                 user: *user,
                 pool: pool_address,
                 authority,
-                amm_config: *AMM_CONFIG,
+                amm_config: *amm_config,
                 token_mint_x: *token_x,
                 token_mint_y: *token_y,
                 token_mint_wsol: native_mint::ID,
@@ -858,15 +1031,22 @@ impl Amm for DarklakeAmm {
                 user_token_account_x,
                 user_token_account_y,
                 user_token_account_lp,
-                pool_token_reserve_x: DarklakeAmm::get_pool_reserve(pool_address, *token_x),
-                pool_token_reserve_y: DarklakeAmm::get_pool_reserve(pool_address, *token_y),
-                pool_wsol_reserve: DarklakeAmm::get_pool_wsol_reserve(pool_address),
-                create_pool_fee_vault: if is_devnet {
-                    DEVNET_CREATE_POOL_FEE_VAULT
-                } else {
-                    MAINNET_CREATE_POOL_FEE_VAULT
-                },
-                mpl_program: METADATA_PROGRAM_ID,
+                pool_token_reserve_x: DarklakeAmm::get_pool_reserve(
+                    &self.network.program_id,
+                    pool_address,
+                    *token_x,
+                ),
+                pool_token_reserve_y: DarklakeAmm::get_pool_reserve(
+                    &self.network.program_id,
+                    pool_address,
+                    *token_y,
+                ),
+                pool_wsol_reserve: DarklakeAmm::get_pool_wsol_reserve(
+                    &self.network.program_id,
+                    pool_address,
+                ),
+                create_pool_fee_vault: self.network.create_pool_fee_vault,
+                mpl_program: self.network.metadata_program_id,
                 system_program: system_program::ID,
                 rent: Rent::id(),
                 associated_token_program: spl_associated_token_account::ID,
@@ -880,7 +1060,270 @@ impl Amm for DarklakeAmm {
 }
 
 impl DarklakeAmm {
-    fn parse_token_account_balance(
+    /// Load a pool of the given Darklake deployment, `load_pool` assumes mainnet
+    pub(crate) fn load_pool_with_network(
+        pool: &KeyedAccount,
+        network: DarklakeNetworkConfig,
+    ) -> Result<Self> {
+        Ok(DarklakeAmm {
+            key: pool.key,
+            pool: Pool::try_from_program_account_data(
+                &pool.account.data,
+                &pool.account.owner,
+                &network.program_id,
+            )?,
+            amm_config: AmmConfig {
+                trade_fee_rate: 0,
+                create_pool_fee: 0,
+                protocol_fee_rate: 0,
+                wsol_trade_deposit: 0,
+                deadline_slot_duration: 0,
+                ratio_change_tolerance_rate: 0,
+                bump: 0,
+                halted: false,
+                padding: [0; 16],
+            },
+            reserve_x_balance: 0,
+            reserve_y_balance: 0,
+            token_x_owner: Pubkey::default(),
+            token_y_owner: Pubkey::default(),
+            token_x_transfer_fee_config: None,
+            token_y_transfer_fee_config: None,
+            token_x_extensions: MintExtensions::default(),
+            token_y_extensions: MintExtensions::default(),
+            network,
+        })
+    }
+
+    /// Reserve balances available to trade against, excluding fees and amounts locked in orders
+    fn get_available_reserves(&self) -> (u64, u64) {
+        let available_x = self
+            .reserve_x_balance
+            .saturating_sub(self.pool.protocol_fee_x)
+            .saturating_sub(self.pool.user_locked_x)
+            .saturating_sub(self.pool.locked_x)
+            .saturating_sub(self.pool.lp_fee_x);
+        let available_y = self
+            .reserve_y_balance
+            .saturating_sub(self.pool.protocol_fee_y)
+            .saturating_sub(self.pool.user_locked_y)
+            .saturating_sub(self.pool.locked_y)
+            .saturating_sub(self.pool.lp_fee_y);
+
+        (available_x, available_y)
+    }
+
+    /// Halted flag and reserve balances of the pool
+    pub(crate) fn get_pool_status(&self) -> PoolStatus {
+        let (available_reserve_x, available_reserve_y) = self.get_available_reserves();
+
+        PoolStatus {
+            key: self.key,
+            halted: self.amm_config.halted,
+            reserve_x_balance: self.reserve_x_balance,
+            reserve_y_balance: self.reserve_y_balance,
+            available_reserve_x,
+            available_reserve_y,
+            token_lp_supply: self.pool.token_lp_supply,
+        }
+    }
+
+    /// Quote the deposit needed to add liquidity with the given amount of one of the pool tokens
+    ///
+    /// The LP amount is rounded down and the deposit for it rounded up, like the program does, so
+    /// the returned amount of `token_mint` can be slightly below `amount`. Amounts are what the
+    /// pool receives, before any Token-2022 transfer fee.
+    pub(crate) fn quote_add_liquidity(
+        &self,
+        token_mint: &Pubkey,
+        amount: u64,
+    ) -> Result<LiquidityQuote> {
+        let (reserve_x, reserve_y) = self.get_available_reserves();
+        let lp_supply = self.pool.token_lp_supply;
+
+        if lp_supply == 0 || reserve_x == 0 || reserve_y == 0 {
+            bail!("Pool has no liquidity");
+        }
+
+        let reserve_in = if *token_mint == self.pool.token_mint_x {
+            reserve_x
+        } else if *token_mint == self.pool.token_mint_y {
+            reserve_y
+        } else {
+            bail!("Token {} is not part of pool {}", token_mint, self.key);
+        };
+
+        let amount_lp = u64::try_from(amount as u128 * lp_supply as u128 / reserve_in as u128)
+            .context("LP amount overflow")?;
+        if amount_lp == 0 {
+            bail!("Amount too small to mint LP tokens");
+        }
+
+        Ok(LiquidityQuote {
+            token_mint_x: self.pool.token_mint_x,
+            token_mint_y: self.pool.token_mint_y,
+            amount_x: lp_to_token_amount(amount_lp, reserve_x, lp_supply, true)?,
+            amount_y: lp_to_token_amount(amount_lp, reserve_y, lp_supply, true)?,
+            amount_lp,
+        })
+    }
+
+    /// Quote the tokens returned for burning the given amount of LP tokens
+    ///
+    /// Amounts are rounded down and exclude locked order amounts and accumulated fees.
+    pub(crate) fn quote_remove_liquidity(&self, amount_lp: u64) -> Result<LiquidityQuote> {
+        let (reserve_x, reserve_y) = self.get_available_reserves();
+        let lp_supply = self.pool.token_lp_supply;
+
+        if amount_lp > lp_supply {
+            bail!(
+                "LP amount {} exceeds the LP supply {}",
+                amount_lp,
+                lp_supply
+            );
+        }
+
+        Ok(LiquidityQuote {
+            token_mint_x: self.pool.token_mint_x,
+            token_mint_y: self.pool.token_mint_y,
+            amount_x: lp_to_token_amount(amount_lp, reserve_x, lp_supply, false)?,
+            amount_y: lp_to_token_amount(amount_lp, reserve_y, lp_supply, false)?,
+            amount_lp,
+        })
+    }
+
+    /// Split a single token deposit into a swap and a liquidity deposit
+    ///
+    /// Finds the smallest swap after which the remaining input and the swap output match the
+    /// post-swap reserve ratio, then quotes the deposit against the post-swap reserves.
+    pub(crate) fn quote_zap(
+        &self,
+        token_in: &Pubkey,
+        amount_in: u64,
+        epoch: u64,
+    ) -> Result<ZapQuote> {
+        let is_x_in = if *token_in == self.pool.token_mint_x {
+            true
+        } else if *token_in == self.pool.token_mint_y {
+            false
+        } else {
+            bail!("Token {} is not part of pool {}", token_in, self.key);
+        };
+
+        let (available_x, available_y) = self.get_available_reserves();
+        let (reserve_in, reserve_out) = if is_x_in {
+            (available_x as u128, available_y as u128)
+        } else {
+            (available_y as u128, available_x as u128)
+        };
+
+        let quote_swap = |swap_amount: u64| {
+            self.quote(&QuoteParams {
+                input_mint: *token_in,
+                amount: swap_amount,
+                swap_mode: SwapMode::ExactIn,
+                epoch,
+            })
+        };
+
+        // remaining input / post-swap input reserve <= output / post-swap output reserve
+        let (mut low, mut high) = (0, amount_in);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let out = if mid == 0 {
+                0
+            } else {
+                // a swap too small to produce any output is part of the search, other errors are not
+                match quote_swap(mid) {
+                    Ok(quote) => quote.out_amount as u128,
+                    Err(e) if e.downcast_ref() == Some(&QuoteError::OutputIsZero) => 0,
+                    Err(e) => return Err(e),
+                }
+            };
+
+            if (amount_in - mid) as u128 * reserve_out.saturating_sub(out)
+                <= out * (reserve_in + mid as u128)
+            {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        let swap_amount = low;
+        let swap_quote = quote_swap(swap_amount)?;
+        let amount_in_remaining = amount_in - swap_amount;
+
+        let mut post_swap = self.clone();
+        let (reserve_in_balance, reserve_out_balance) = if is_x_in {
+            (
+                &mut post_swap.reserve_x_balance,
+                &mut post_swap.reserve_y_balance,
+            )
+        } else {
+            (
+                &mut post_swap.reserve_y_balance,
+                &mut post_swap.reserve_x_balance,
+            )
+        };
+        *reserve_in_balance += swap_amount - swap_quote.input_transfer_fee;
+        *reserve_out_balance = reserve_out_balance
+            .saturating_sub(swap_quote.out_amount + swap_quote.output_transfer_fee);
+
+        let token_other = if is_x_in {
+            self.pool.token_mint_y
+        } else {
+            self.pool.token_mint_x
+        };
+
+        let liquidity_quote = post_swap.quote_add_liquidity(&token_other, swap_quote.out_amount)?;
+        let required_amount_in = if is_x_in {
+            liquidity_quote.amount_x
+        } else {
+            liquidity_quote.amount_y
+        };
+        let liquidity_quote = if required_amount_in > amount_in_remaining {
+            post_swap.quote_add_liquidity(token_in, amount_in_remaining)?
+        } else {
+            liquidity_quote
+        };
+
+        Ok(ZapQuote {
+            token_in: *token_in,
+            amount_in,
+            swap_amount,
+            swap_quote,
+            liquidity_quote,
+        })
+    }
+
+    /// Value an amount of this pool's LP tokens
+    pub(crate) fn get_lp_position(&self, amount_lp: u64) -> Result<LpPosition> {
+        let lp_supply = self.pool.token_lp_supply;
+        let remove_liquidity_quote = self.quote_remove_liquidity(amount_lp)?;
+
+        let share = if lp_supply == 0 {
+            Decimal::ZERO
+        } else {
+            Decimal::from(amount_lp) / Decimal::from(lp_supply)
+        };
+
+        Ok(LpPosition {
+            pool: self.key,
+            token_mint_lp: DarklakeAmm::get_token_mint_lp(&self.network.program_id, self.key),
+            token_mint_x: self.pool.token_mint_x,
+            token_mint_y: self.pool.token_mint_y,
+            amount_lp,
+            lp_supply,
+            share,
+            amount_x: remove_liquidity_quote.amount_x,
+            amount_y: remove_liquidity_quote.amount_y,
+            lp_fee_x: lp_to_token_amount(amount_lp, self.pool.lp_fee_x, lp_supply, false)?,
+            lp_fee_y: lp_to_token_amount(amount_lp, self.pool.lp_fee_y, lp_supply, false)?,
+        })
+    }
+
+    pub(crate) fn parse_token_account_balance(
         account_data: &[u8],
         account_owner: &Pubkey,
         token_account_pubkey: &Pubkey,
@@ -905,28 +1348,69 @@ impl DarklakeAmm {
         }
     }
 
-    fn get_transfer_fee_config(
+    /// Transfers of transfer hook mints between a user and the pool reserves
+    ///
+    /// # Arguments
+    /// * `user` - The user sending to or receiving from the pool
+    /// * `transfers` - `(token_mint, is_into_pool, amount)` for every leg of the instruction
+    ///
+    /// # Returns
+    /// Returns the legs whose mint has a transfer hook, with the mint extensions, or an error if a
+    /// mint is not one of the pool's
+    pub(crate) fn get_transfer_hook_transfers(
         &self,
-        mint_account_data: &[u8],
-        mint_owner: &Pubkey,
-    ) -> Result<Option<TransferFeeConfig>, ()> {
-        if *mint_owner != spl_token_2022::ID {
-            return Ok(None);
-        }
+        user: &Pubkey,
+        transfers: &[(Pubkey, bool, u64)],
+    ) -> Result<Vec<(TokenTransfer, &MintExtensions)>> {
+        let mut transfer_hook_transfers = vec![];
+
+        for (token_mint, is_into_pool, amount) in transfers {
+            let (reserve, token_program, mint_extensions) = if *token_mint == self.pool.token_mint_x
+            {
+                (
+                    self.pool.reserve_x,
+                    self.token_x_owner,
+                    &self.token_x_extensions,
+                )
+            } else if *token_mint == self.pool.token_mint_y {
+                (
+                    self.pool.reserve_y,
+                    self.token_y_owner,
+                    &self.token_y_extensions,
+                )
+            } else {
+                bail!("Mint {} is not traded by pool {}", token_mint, self.key);
+            };
 
-        match StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_account_data) {
-            Ok(mint) => {
-                match mint.get_extension::<TransferFeeConfig>() {
-                    Ok(transfer_fee_config) => Ok(Some(transfer_fee_config.clone())),
-                    Err(_) => Ok(None), // Extension not found or error getting it
-                }
-            }
-            Err(_) => {
-                // Not a valid Token2022 mint or some other error occurred
-                // Return None instead of an error, indicating no transfer fee config
-                Ok(None)
+            if mint_extensions.transfer_hook_program_id.is_none() {
+                continue;
             }
+
+            let user_token_account =
+                DarklakeAmm::get_user_token_account(*user, *token_mint, token_program);
+
+            let transfer = if *is_into_pool {
+                TokenTransfer {
+                    mint: *token_mint,
+                    source: user_token_account,
+                    destination: reserve,
+                    authority: *user,
+                    amount: *amount,
+                }
+            } else {
+                TokenTransfer {
+                    mint: *token_mint,
+                    source: reserve,
+                    destination: user_token_account,
+                    authority: self.network.authority(),
+                    amount: *amount,
+                }
+            };
+
+            transfer_hook_transfers.push((transfer, mint_extensions));
         }
+
+        Ok(transfer_hook_transfers)
     }
 
     pub fn get_user_token_account(
@@ -941,12 +1425,8 @@ impl DarklakeAmm {
         .0
     }
 
-    pub fn get_pool_wsol_reserve(pool: Pubkey) -> Pubkey {
-        Pubkey::find_program_address(
-            &[POOL_WSOL_RESERVE_SEED, pool.as_ref()],
-            &DARKLAKE_PROGRAM_ID,
-        )
-        .0
+    pub fn get_pool_wsol_reserve(program_id: &Pubkey, pool: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[POOL_WSOL_RESERVE_SEED, pool.as_ref()], program_id).0
     }
 
     fn get_order(&self, user: &Pubkey) -> Pubkey {
@@ -957,8 +1437,8 @@ impl DarklakeAmm {
         .0
     }
 
-    pub fn get_token_mint_lp(pool: Pubkey) -> Pubkey {
-        Pubkey::find_program_address(&[LIQUIDITY_SEED, pool.as_ref()], &DARKLAKE_PROGRAM_ID).0
+    pub fn get_token_mint_lp(program_id: &Pubkey, pool: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[LIQUIDITY_SEED, pool.as_ref()], program_id).0
     }
 
     fn get_order_token_account_wsol(&self, user: Pubkey) -> Pubkey {
@@ -969,35 +1449,40 @@ impl DarklakeAmm {
         .0
     }
 
-    pub fn get_pool_address(token_mint_x: &Pubkey, token_mint_y: &Pubkey) -> Pubkey {
+    pub fn get_pool_address(
+        program_id: &Pubkey,
+        amm_config: &Pubkey,
+        token_mint_x: &Pubkey,
+        token_mint_y: &Pubkey,
+    ) -> Pubkey {
         Pubkey::find_program_address(
             &[
                 POOL_SEED,
-                &AMM_CONFIG.as_ref(),
+                amm_config.as_ref(),
                 token_mint_x.as_ref(),
                 token_mint_y.as_ref(),
             ],
-            &DARKLAKE_PROGRAM_ID,
+            program_id,
         )
         .0
     }
 
-    pub fn get_token_metadata(token_mint: Pubkey) -> Pubkey {
+    pub fn get_token_metadata(metadata_program_id: &Pubkey, token_mint: Pubkey) -> Pubkey {
         Pubkey::find_program_address(
             &[
                 METADATA_SEED,
-                &METADATA_PROGRAM_ID.as_ref(),
+                metadata_program_id.as_ref(),
                 token_mint.as_ref(),
             ],
-            &METADATA_PROGRAM_ID,
+            metadata_program_id,
         )
         .0
     }
 
-    pub fn get_pool_reserve(pool: Pubkey, token_mint: Pubkey) -> Pubkey {
+    pub fn get_pool_reserve(program_id: &Pubkey, pool: Pubkey, token_mint: Pubkey) -> Pubkey {
         Pubkey::find_program_address(
             &[POOL_RESERVE_SEED, pool.as_ref(), token_mint.as_ref()],
-            &DARKLAKE_PROGRAM_ID,
+            program_id,
         )
         .0
     }
@@ -1006,3 +1491,118 @@ impl DarklakeAmm {
         (self.token_x_owner, self.token_y_owner)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_data<T: AnchorSerialize>(discriminator: &[u8; 8], account: &T) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        account.serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_account_len_matches_layout() {
+        assert_eq!(Pool::default().try_to_vec().unwrap().len(), Pool::LEN);
+        assert_eq!(
+            AmmConfig::default().try_to_vec().unwrap().len(),
+            AmmConfig::LEN
+        );
+
+        let order = Order {
+            trader: Pubkey::new_unique(),
+            token_mint_x: Pubkey::new_unique(),
+            token_mint_y: Pubkey::new_unique(),
+            actual_in: 0,
+            exchange_in: 0,
+            actual_out: 0,
+            from_to_lock: 0,
+            d_in: 0,
+            d_out: 0,
+            deadline: 0,
+            protocol_fee: 0,
+            wsol_deposit: 0,
+            c_min: [0; 32],
+            is_x_to_y: false,
+            bump: 0,
+            lp_fee: 0,
+            padding: [0; 3],
+        };
+        assert_eq!(order.try_to_vec().unwrap().len(), Order::LEN);
+    }
+
+    #[test]
+    fn test_pool_try_from_account_data() {
+        let pool = Pool {
+            token_mint_x: Pubkey::new_unique(),
+            token_lp_supply: 1_000,
+            ..Pool::default()
+        };
+        let data = account_data(&POOL_DISCRIMINATOR, &pool);
+
+        let decoded = Pool::try_from_account_data(&data, &DARKLAKE_PROGRAM_ID).unwrap();
+        assert_eq!(decoded.token_mint_x, pool.token_mint_x);
+        assert_eq!(decoded.token_lp_supply, 1_000);
+    }
+
+    #[test]
+    fn test_account_decode_errors() {
+        let data = account_data(&POOL_DISCRIMINATOR, &Pool::default());
+
+        let wrong_owner = Pubkey::new_unique();
+        assert_eq!(
+            Pool::try_from_account_data(&data, &wrong_owner).unwrap_err(),
+            AccountDecodeError::InvalidOwner {
+                expected: DARKLAKE_PROGRAM_ID,
+                actual: wrong_owner,
+            }
+        );
+
+        assert_eq!(
+            Pool::try_from_account_data(&data[..40], &DARKLAKE_PROGRAM_ID).unwrap_err(),
+            AccountDecodeError::AccountDataTooShort {
+                expected: 8 + Pool::LEN,
+                actual: 40,
+            }
+        );
+
+        // a pool account is not an amm config
+        let data = account_data(&POOL_DISCRIMINATOR, &Pool::default());
+        assert!(matches!(
+            AmmConfig::try_from_account_data(&data, &DARKLAKE_PROGRAM_ID).unwrap_err(),
+            AccountDecodeError::InvalidDiscriminator { .. }
+        ));
+    }
+
+    #[test]
+    fn test_custom_network_decodes_and_derives_with_its_program_id() {
+        let network = DarklakeNetworkConfig::custom(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let data = account_data(&POOL_DISCRIMINATOR, &Pool::default());
+
+        assert!(Pool::try_from_account_data(&data, &network.program_id).is_err());
+
+        let amm = DarklakeAmm::load_pool_with_network(
+            &KeyedAccount {
+                key: Pubkey::new_unique(),
+                account: AccountData {
+                    data,
+                    owner: network.program_id,
+                },
+            },
+            network.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(amm.program_id(), network.program_id);
+        assert_eq!(
+            DarklakeAmm::get_token_mint_lp(&network.program_id, amm.key),
+            Pubkey::find_program_address(&[LIQUIDITY_SEED, amm.key.as_ref()], &network.program_id)
+                .0
+        );
+    }
+}
@@ -0,0 +1,571 @@
+use anyhow::{Context, Result, bail};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    amm::{
+        AccountMap, Amm, KeyedAccount, LiquidityQuote, LpPosition, PoolStatus, Quote, QuoteParams,
+        SwapMode, ZapQuote,
+    },
+    darklake_amm::{AmmConfig, DarklakeAmm, Pool},
+    network::DarklakeNetworkConfig,
+    token_extensions::MintExtensions,
+};
+
+/// RPC-free Darklake pool that quotes from caller supplied account data
+///
+/// Feed it from an account cache or a Geyser stream: build it once from the pool, AmmConfig,
+/// reserve and mint accounts, then call `update` whenever any of `accounts_to_update` changes.
+#[derive(Clone)]
+pub struct DarklakePool {
+    amm: DarklakeAmm,
+    epoch: u64,
+}
+
+impl DarklakePool {
+    /// Build a pool from raw account data
+    ///
+    /// # Arguments
+    /// * `pool_key` - The pool address
+    /// * `account_map` - Account data keyed by address, must contain the pool, its AmmConfig,
+    ///   both reserves and both mints
+    /// * `epoch` - The current epoch, used for Token-2022 transfer fees
+    ///
+    /// # Returns
+    /// Returns a `DarklakePool` ready to quote
+    pub fn from_account_map(
+        pool_key: &Pubkey,
+        account_map: &AccountMap,
+        epoch: u64,
+    ) -> Result<Self> {
+        Self::from_account_map_with_network(
+            pool_key,
+            account_map,
+            epoch,
+            DarklakeNetworkConfig::mainnet(),
+        )
+    }
+
+    /// Build a pool of a custom Darklake deployment from raw account data
+    ///
+    /// Same as `from_account_map`, but accounts must be owned by the program of `network`.
+    pub fn from_account_map_with_network(
+        pool_key: &Pubkey,
+        account_map: &AccountMap,
+        epoch: u64,
+        network: DarklakeNetworkConfig,
+    ) -> Result<Self> {
+        let pool_account = account_map
+            .get(pool_key)
+            .context("Darklake pool account not found")?;
+
+        let mut amm = DarklakeAmm::load_pool_with_network(
+            &KeyedAccount {
+                key: *pool_key,
+                account: pool_account.clone(),
+            },
+            network,
+        )?;
+        amm.update(account_map)?;
+
+        Ok(Self { amm, epoch })
+    }
+
+    /// Accounts required by `from_account_map` and `update` for a decoded pool
+    ///
+    /// # Arguments
+    /// * `pool_key` - The pool address
+    /// * `pool` - The decoded pool account
+    ///
+    /// # Returns
+    /// Returns the pool, mint, reserve and AmmConfig addresses
+    pub fn required_accounts(pool_key: &Pubkey, pool: &Pool) -> Vec<Pubkey> {
+        vec![
+            *pool_key,
+            pool.token_mint_x,
+            pool.token_mint_y,
+            pool.reserve_x,
+            pool.reserve_y,
+            pool.amm_config,
+        ]
+    }
+
+    /// Accounts this pool reads in `update`
+    pub fn accounts_to_update(&self) -> Vec<Pubkey> {
+        self.amm.get_accounts_to_update()
+    }
+
+    /// Refresh the pool state from raw account data
+    ///
+    /// # Arguments
+    /// * `account_map` - Account data keyed by address, must contain all of `accounts_to_update`
+    pub fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        self.amm.update(account_map)
+    }
+
+    /// Set the epoch used for Token-2022 transfer fees
+    pub fn set_epoch(&mut self, epoch: u64) {
+        self.epoch = epoch;
+    }
+
+    /// Get a quote for an exact in swap
+    ///
+    /// # Arguments
+    /// * `token_in` - The input token mint, must be one of the pool mints (WSOL for SOL)
+    /// * `amount_in` - The amount of input tokens
+    ///
+    /// # Returns
+    /// Returns a `Quote`
+    pub fn quote(&self, token_in: &Pubkey, amount_in: u64) -> Result<Quote> {
+        if *token_in != self.amm.pool.token_mint_x && *token_in != self.amm.pool.token_mint_y {
+            bail!("Token {} is not part of pool {}", token_in, self.amm.key);
+        }
+
+        self.amm.quote(&QuoteParams {
+            input_mint: *token_in,
+            amount: amount_in,
+            swap_mode: SwapMode::ExactIn,
+            epoch: self.epoch,
+        })
+    }
+
+    /// Quote the deposit needed to add liquidity
+    ///
+    /// # Arguments
+    /// * `token_mint` - The pool token the amount is given in
+    /// * `amount` - The amount of `token_mint` to deposit
+    ///
+    /// # Returns
+    /// Returns a `LiquidityQuote` with the LP tokens minted and the required x and y amounts
+    pub fn quote_add_liquidity(&self, token_mint: &Pubkey, amount: u64) -> Result<LiquidityQuote> {
+        self.amm.quote_add_liquidity(token_mint, amount)
+    }
+
+    /// Quote the tokens returned for burning LP tokens
+    ///
+    /// # Arguments
+    /// * `amount_lp` - The amount of LP tokens to burn
+    ///
+    /// # Returns
+    /// Returns a `LiquidityQuote` with the x and y amounts received
+    pub fn quote_remove_liquidity(&self, amount_lp: u64) -> Result<LiquidityQuote> {
+        self.amm.quote_remove_liquidity(amount_lp)
+    }
+
+    /// Quote a single token liquidity deposit (zap)
+    ///
+    /// # Arguments
+    /// * `token_in` - The pool token deposited
+    /// * `amount_in` - The amount of `token_in` to deposit
+    ///
+    /// # Returns
+    /// Returns a `ZapQuote` with the part of `amount_in` to swap and the expected deposit
+    pub fn quote_zap(&self, token_in: &Pubkey, amount_in: u64) -> Result<ZapQuote> {
+        self.amm.quote_zap(token_in, amount_in, self.epoch)
+    }
+
+    /// Value an amount of this pool's LP tokens
+    ///
+    /// # Arguments
+    /// * `amount_lp` - The amount of LP tokens held
+    ///
+    /// # Returns
+    /// Returns an `LpPosition` with the pool share, underlying amounts and share of LP fees
+    pub fn lp_position(&self, amount_lp: u64) -> Result<LpPosition> {
+        self.amm.get_lp_position(amount_lp)
+    }
+
+    /// The pool address
+    pub fn key(&self) -> Pubkey {
+        self.amm.key
+    }
+
+    /// The decoded pool account
+    pub fn pool(&self) -> &Pool {
+        &self.amm.pool
+    }
+
+    /// The decoded AmmConfig account
+    pub fn amm_config(&self) -> &AmmConfig {
+        &self.amm.amm_config
+    }
+
+    /// The reserve balances as (x, y)
+    pub fn reserve_balances(&self) -> (u64, u64) {
+        (self.amm.reserve_x_balance, self.amm.reserve_y_balance)
+    }
+
+    /// The Token-2022 extensions of the mints as (x, y)
+    pub fn mint_extensions(&self) -> (&MintExtensions, &MintExtensions) {
+        (&self.amm.token_x_extensions, &self.amm.token_y_extensions)
+    }
+
+    /// The epoch used for Token-2022 transfer fees
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Whether the pool accepts trades
+    pub fn is_active(&self) -> bool {
+        self.amm.is_active()
+    }
+
+    /// Halted flag, reserve balances and available reserves of the pool
+    pub fn status(&self) -> PoolStatus {
+        self.amm.get_pool_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amm::AccountData;
+    use crate::constants::{AMM_CONFIG_DISCRIMINATOR, POOL_DISCRIMINATOR};
+    use crate::error::QuoteError;
+    use crate::test_utils::{
+        anchor_account, pool_accounts, token_2022_account, token_2022_mint_account, token_account,
+        transfer_fee_config, transfer_fee_mint_account,
+    };
+    use rust_decimal::Decimal;
+    use spl_pod::optional_keys::OptionalNonZeroPubkey;
+    use spl_token_2022::extension::{
+        BaseStateWithExtensionsMut, ExtensionType, non_transferable::NonTransferable,
+        transfer_hook::TransferHook,
+    };
+
+    fn test_accounts(reserve_x: u64, reserve_y: u64) -> (Pubkey, Pool, AccountMap) {
+        let (token_mint_a, token_mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (token_mint_x, token_mint_y) = if token_mint_a < token_mint_b {
+            (token_mint_a, token_mint_b)
+        } else {
+            (token_mint_b, token_mint_a)
+        };
+
+        pool_accounts(token_mint_x, token_mint_y, reserve_x, reserve_y)
+    }
+
+    #[test]
+    fn test_quote_from_account_data() {
+        let (pool_key, pool, account_map) = test_accounts(1_000_000_000, 2_000_000_000);
+
+        assert_eq!(
+            DarklakePool::required_accounts(&pool_key, &pool).len(),
+            account_map.len()
+        );
+
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+        assert_eq!(darklake_pool.key(), pool_key);
+        assert_eq!(
+            darklake_pool.reserve_balances(),
+            (1_000_000_000, 2_000_000_000)
+        );
+
+        let quote = darklake_pool.quote(&pool.token_mint_x, 1_000_000).unwrap();
+        assert!(quote.out_amount > 0 && quote.out_amount < 2_000_000);
+        assert_eq!(quote.fee_mint, pool.token_mint_x);
+    }
+
+    #[test]
+    fn test_quote_prices_and_fees() {
+        let (pool_key, pool, account_map) = test_accounts(1_000_000_000, 2_000_000_000);
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+
+        let small = darklake_pool.quote(&pool.token_mint_x, 1_000_000).unwrap();
+        let large = darklake_pool
+            .quote(&pool.token_mint_x, 100_000_000)
+            .unwrap();
+
+        // 3_000 / 1_000_000 = 0.3%
+        assert_eq!(small.fee_pct, Decimal::new(3, 1));
+        assert_eq!(small.spot_price, Decimal::from(2));
+        assert!(small.execution_price < small.spot_price);
+        assert!(large.execution_price < small.execution_price);
+        assert!(large.price_impact_bps > small.price_impact_bps);
+        assert!(small.protocol_fee <= small.fee_amount);
+        assert_eq!(small.input_transfer_fee, 0);
+        assert_eq!(small.output_transfer_fee, 0);
+    }
+
+    #[test]
+    fn test_tiny_trade_has_no_price_impact() {
+        let (pool_key, pool, account_map) =
+            test_accounts(1_000_000_000_000_000, 2_000_000_000_000_000);
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+
+        let quote = darklake_pool.quote(&pool.token_mint_x, 1_000_000).unwrap();
+
+        assert_eq!(quote.price_impact_bps, 0);
+        // the trade fee still lowers the execution price
+        assert!(quote.execution_price < quote.spot_price);
+    }
+
+    #[test]
+    fn test_update_refreshes_reserves() {
+        let (pool_key, pool, mut account_map) = test_accounts(1_000_000_000, 1_000_000_000);
+        let mut darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+        let before = darklake_pool.quote(&pool.token_mint_y, 1_000_000).unwrap();
+
+        account_map.insert(
+            pool.reserve_x,
+            token_account(pool.token_mint_x, 2_000_000_000),
+        );
+        darklake_pool.update(&account_map).unwrap();
+        let after = darklake_pool.quote(&pool.token_mint_y, 1_000_000).unwrap();
+
+        assert_eq!(darklake_pool.reserve_balances().0, 2_000_000_000);
+        assert!(after.out_amount > before.out_amount);
+    }
+
+    #[test]
+    fn test_rejects_missing_accounts_and_foreign_mints() {
+        let (pool_key, pool, mut account_map) = test_accounts(1_000_000_000, 1_000_000_000);
+
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+        assert!(darklake_pool.quote(&Pubkey::new_unique(), 1_000).is_err());
+
+        account_map.remove(&pool.amm_config);
+        assert!(DarklakePool::from_account_map(&pool_key, &account_map, 0).is_err());
+    }
+
+    #[test]
+    fn test_quote_add_liquidity() {
+        let (pool_key, pool, account_map) = test_accounts(1_000_000, 4_000_000);
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+
+        // lp supply is 1_000_000, so 1 lp = 1 x = 4 y
+        let quote = darklake_pool
+            .quote_add_liquidity(&pool.token_mint_y, 4_000)
+            .unwrap();
+        assert_eq!(quote.amount_lp, 1_000);
+        assert_eq!(quote.amount_x, 1_000);
+        assert_eq!(quote.amount_y, 4_000);
+
+        // lp rounds down, deposits round up
+        let quote = darklake_pool
+            .quote_add_liquidity(&pool.token_mint_y, 4_003)
+            .unwrap();
+        assert_eq!(quote.amount_lp, 1_000);
+        assert_eq!(quote.amount_y, 4_000);
+
+        assert!(
+            darklake_pool
+                .quote_add_liquidity(&pool.token_mint_y, 3)
+                .is_err()
+        );
+        assert!(
+            darklake_pool
+                .quote_add_liquidity(&Pubkey::new_unique(), 1_000)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_quote_remove_liquidity_excludes_locked_and_fees() {
+        let (pool_key, mut pool, mut account_map) = test_accounts(1_000_000, 4_000_000);
+        pool.locked_y = 1_000_000;
+        pool.lp_fee_x = 100_000;
+        pool.protocol_fee_x = 100_000;
+        account_map.insert(pool_key, anchor_account(&POOL_DISCRIMINATOR, &pool));
+
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+
+        let quote = darklake_pool.quote_remove_liquidity(500_000).unwrap();
+        assert_eq!(quote.amount_x, 400_000);
+        assert_eq!(quote.amount_y, 1_500_000);
+        assert_eq!(quote.token_mint_x, pool.token_mint_x);
+
+        assert!(darklake_pool.quote_remove_liquidity(1_000_001).is_err());
+    }
+
+    #[test]
+    fn test_lp_position() {
+        let (pool_key, mut pool, mut account_map) = test_accounts(1_000_000, 4_000_000);
+        pool.lp_fee_x = 10_000;
+        pool.lp_fee_y = 40_000;
+        account_map.insert(pool_key, anchor_account(&POOL_DISCRIMINATOR, &pool));
+
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+
+        let position = darklake_pool.lp_position(250_000).unwrap();
+        assert_eq!(position.share, Decimal::new(25, 2));
+        assert_eq!(position.amount_x, 247_500);
+        assert_eq!(position.amount_y, 990_000);
+        assert_eq!(position.lp_fee_x, 2_500);
+        assert_eq!(position.lp_fee_y, 10_000);
+
+        let empty_position = darklake_pool.lp_position(0).unwrap();
+        assert_eq!(empty_position.amount_x, 0);
+        assert_eq!(empty_position.share, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_quote_zap() {
+        let (pool_key, pool, account_map) = test_accounts(1_000_000_000, 1_000_000_000);
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+
+        let zap_quote = darklake_pool
+            .quote_zap(&pool.token_mint_x, 10_000_000)
+            .unwrap();
+
+        // roughly half is swapped for a balanced pool
+        assert!(zap_quote.swap_amount > 4_900_000 && zap_quote.swap_amount < 5_100_000);
+
+        let liquidity_quote = &zap_quote.liquidity_quote;
+        assert!(liquidity_quote.amount_x <= 10_000_000 - zap_quote.swap_amount);
+        assert!(liquidity_quote.amount_y <= zap_quote.swap_quote.out_amount);
+        // little of either side is left undeposited
+        assert!(10_000_000 - zap_quote.swap_amount - liquidity_quote.amount_x < 20_000);
+        assert!(zap_quote.swap_quote.out_amount - liquidity_quote.amount_y < 20_000);
+
+        assert!(
+            darklake_pool
+                .quote_zap(&Pubkey::new_unique(), 1_000)
+                .is_err()
+        );
+
+        // a swap of 1 has no output, which the search treats as zero instead of failing
+        let err = darklake_pool.quote(&pool.token_mint_x, 1).unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&QuoteError::OutputIsZero));
+    }
+
+    #[test]
+    fn test_mint_extensions() {
+        let (pool_key, pool, mut account_map) = test_accounts(1_000_000_000, 1_000_000_000);
+        let transfer_hook_program_id = Pubkey::new_unique();
+        account_map.insert(
+            pool.token_mint_x,
+            token_2022_mint_account(&[ExtensionType::TransferHook], |state| {
+                state
+                    .init_extension::<TransferHook>(true)
+                    .unwrap()
+                    .program_id =
+                    OptionalNonZeroPubkey::try_from(Some(transfer_hook_program_id)).unwrap();
+            }),
+        );
+
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+        let (token_x_extensions, token_y_extensions) = darklake_pool.mint_extensions();
+        assert_eq!(
+            token_x_extensions.transfer_hook_program_id,
+            Some(transfer_hook_program_id)
+        );
+        assert_eq!(*token_y_extensions, MintExtensions::default());
+
+        // only the hooked leg needs extra accounts
+        let user = Pubkey::new_unique();
+        let transfers = darklake_pool
+            .amm
+            .get_transfer_hook_transfers(
+                &user,
+                &[
+                    (pool.token_mint_x, true, 1_000),
+                    (pool.token_mint_y, false, 1_000),
+                ],
+            )
+            .unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].0.destination, pool.reserve_x);
+        assert_eq!(transfers[0].0.authority, user);
+
+        // a mint of another pool must not get the accounts of a reserve
+        assert!(
+            darklake_pool
+                .amm
+                .get_transfer_hook_transfers(&user, &[(Pubkey::new_unique(), true, 1_000)])
+                .is_err()
+        );
+
+        account_map.insert(
+            pool.token_mint_y,
+            token_2022_mint_account(&[ExtensionType::NonTransferable], |state| {
+                state.init_extension::<NonTransferable>(true).unwrap();
+            }),
+        );
+        assert!(DarklakePool::from_account_map(&pool_key, &account_map, 0).is_err());
+    }
+
+    #[test]
+    fn test_quote_includes_transfer_fees_of_both_legs() {
+        let (pool_key, pool, mut account_map) = test_accounts(1_000_000_000, 1_000_000_000);
+
+        // the pool receives 990_000 of the 1_000_000 sent with a 1% input fee
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+        let to_amount = darklake_pool
+            .quote(&pool.token_mint_x, 990_000)
+            .unwrap()
+            .out_amount;
+
+        // x charges 1%, y charges 1% until epoch 5 and 2% from then on
+        account_map.insert(
+            pool.token_mint_x,
+            transfer_fee_mint_account(transfer_fee_config(100, 100, 0)),
+        );
+        account_map.insert(
+            pool.token_mint_y,
+            transfer_fee_mint_account(transfer_fee_config(100, 200, 5)),
+        );
+        account_map.insert(
+            pool.reserve_x,
+            token_2022_account(pool.token_mint_x, 1_000_000_000),
+        );
+        account_map.insert(
+            pool.reserve_y,
+            token_2022_account(pool.token_mint_y, 1_000_000_000),
+        );
+
+        let mut darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+        assert!(darklake_pool.mint_extensions().0.transfer_fee);
+
+        let quote = darklake_pool.quote(&pool.token_mint_x, 1_000_000).unwrap();
+        assert_eq!(quote.input_transfer_fee, 10_000);
+        assert_eq!(quote.output_transfer_fee, to_amount.div_ceil(100));
+        assert_eq!(quote.out_amount, to_amount - quote.output_transfer_fee);
+
+        darklake_pool.set_epoch(5);
+        let newer_fee_quote = darklake_pool.quote(&pool.token_mint_x, 1_000_000).unwrap();
+        assert_eq!(newer_fee_quote.input_transfer_fee, 10_000);
+        assert_eq!(newer_fee_quote.output_transfer_fee, to_amount.div_ceil(50));
+        assert_eq!(
+            newer_fee_quote.out_amount,
+            to_amount - newer_fee_quote.output_transfer_fee
+        );
+
+        // a malformed Token-2022 mint fails instead of quoting without fees
+        account_map.insert(
+            pool.token_mint_y,
+            AccountData {
+                data: vec![1; 10],
+                owner: spl_token_2022::ID,
+            },
+        );
+        assert!(DarklakePool::from_account_map(&pool_key, &account_map, 0).is_err());
+    }
+
+    #[test]
+    fn test_status() {
+        let (pool_key, mut pool, mut account_map) = test_accounts(1_000_000, 4_000_000);
+        pool.locked_x = 100_000;
+        pool.protocol_fee_y = 200_000;
+        account_map.insert(pool_key, anchor_account(&POOL_DISCRIMINATOR, &pool));
+
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+        let status = darklake_pool.status();
+        assert!(!status.halted);
+        assert_eq!(status.reserve_x_balance, 1_000_000);
+        assert_eq!(status.available_reserve_x, 900_000);
+        assert_eq!(status.available_reserve_y, 3_800_000);
+
+        account_map.insert(
+            pool.amm_config,
+            anchor_account(
+                &AMM_CONFIG_DISCRIMINATOR,
+                &AmmConfig {
+                    halted: true,
+                    ..AmmConfig::default()
+                },
+            ),
+        );
+        let halted_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+        assert!(halted_pool.status().halted);
+        assert!(!halted_pool.is_active());
+    }
+}
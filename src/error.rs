@@ -0,0 +1,104 @@
+use std::fmt;
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Error returned when raw account data cannot be decoded into a Darklake account
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountDecodeError {
+    /// The account is not owned by the Darklake program
+    InvalidOwner { expected: Pubkey, actual: Pubkey },
+    /// The account data does not start with the expected Anchor discriminator
+    InvalidDiscriminator { expected: [u8; 8], actual: Vec<u8> },
+    /// The account data is shorter than the account layout
+    AccountDataTooShort { expected: usize, actual: usize },
+    /// The account data could not be deserialized
+    Deserialize(String),
+}
+
+impl fmt::Display for AccountDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountDecodeError::InvalidOwner { expected, actual } => {
+                write!(
+                    f,
+                    "Invalid account owner, expected {} got {}",
+                    expected, actual
+                )
+            }
+            AccountDecodeError::InvalidDiscriminator { expected, actual } => write!(
+                f,
+                "Invalid account discriminator, expected {:?} got {:?}",
+                expected, actual
+            ),
+            AccountDecodeError::AccountDataTooShort { expected, actual } => write!(
+                f,
+                "Account data too short, expected at least {} bytes got {}",
+                expected, actual
+            ),
+            AccountDecodeError::Deserialize(e) => {
+                write!(f, "Failed to deserialize account data: {}", e)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AccountDecodeError {}
+
+/// Error returned by `swap_ix`/`swap_tx` when a swap would fail on chain
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwapPreflightError {
+    /// The pool's AmmConfig is halted
+    PoolHalted { pool: Pubkey },
+    /// The trader already has an open order in the pool, only one order per pool is allowed
+    OrderExists {
+        order: Pubkey,
+        deadline: u64,
+        is_expired: bool,
+    },
+}
+
+impl fmt::Display for SwapPreflightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapPreflightError::PoolHalted { pool } => write!(f, "Pool {} is halted", pool),
+            SwapPreflightError::OrderExists {
+                order,
+                deadline,
+                is_expired,
+            } => {
+                if *is_expired {
+                    write!(
+                        f,
+                        "Order {} already exists and expired at slot {}, it must be slashed first",
+                        order, deadline
+                    )
+                } else {
+                    write!(
+                        f,
+                        "Order {} already exists until slot {}, it must be settled or cancelled first",
+                        order, deadline
+                    )
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for SwapPreflightError {}
+
+/// Error returned by quotes when a swap cannot be quoted
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteError {
+    /// The swap output is zero after the trade and transfer fees
+    OutputIsZero,
+}
+
+impl fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuoteError::OutputIsZero => write!(f, "Output is zero"),
+        }
+    }
+}
+
+impl std::error::Error for QuoteError {}
@@ -0,0 +1,460 @@
+use anchor_lang::{AnchorDeserialize, AnchorSerialize, prelude::borsh};
+use anyhow::{Context, Result};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::{
+    ADD_LIQUIDITY_EVENT_DISCRIMINATOR, CANCEL_EVENT_DISCRIMINATOR, DARKLAKE_PROGRAM_ID,
+    INITIALIZE_POOL_EVENT_DISCRIMINATOR, REMOVE_LIQUIDITY_EVENT_DISCRIMINATOR,
+    SETTLE_EVENT_DISCRIMINATOR, SLASH_EVENT_DISCRIMINATOR, SWAP_EVENT_DISCRIMINATOR,
+};
+
+const PROGRAM_LOG_PREFIX: &str = "Program ";
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// Emitted when a swap order is created
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwapEvent {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub trader: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_y: Pubkey,
+
+    pub actual_in: u64,
+    pub exchange_in: u64,
+    pub d_in: u64,
+    pub d_out: u64,
+    pub deadline: u64,
+    pub protocol_fee: u64,
+    pub lp_fee: u64,
+    pub wsol_deposit: u64,
+
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
+    pub c_min: [u8; 32],
+    pub is_x_to_y: bool,
+
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
+    pub label: Option<[u8; 21]>,
+}
+
+/// Emitted when an order is settled
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SettleEvent {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub caller: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub trader: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_y: Pubkey,
+
+    pub actual_in: u64,
+    pub actual_out: u64,
+    pub d_in: u64,
+    pub d_out: u64,
+    pub protocol_fee: u64,
+    pub lp_fee: u64,
+
+    pub is_x_to_y: bool,
+    pub unwrap_wsol: bool,
+
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
+    pub ref_code: Option<[u8; 20]>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
+    pub label: Option<[u8; 21]>,
+}
+
+/// Emitted when an order is cancelled
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CancelEvent {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub caller: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub trader: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_y: Pubkey,
+
+    pub actual_in: u64,
+    pub d_in: u64,
+    pub d_out: u64,
+
+    pub is_x_to_y: bool,
+
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
+    pub label: Option<[u8; 21]>,
+}
+
+/// Emitted when an expired order is slashed
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlashEvent {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub caller: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub trader: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_y: Pubkey,
+
+    pub actual_in: u64,
+    pub deadline: u64,
+    pub wsol_deposit: u64,
+
+    pub is_x_to_y: bool,
+
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
+    pub label: Option<[u8; 21]>,
+}
+
+/// Emitted when liquidity is added to a pool
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddLiquidityEvent {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub user: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_y: Pubkey,
+
+    pub amount_lp: u64,
+    pub amount_x: u64,
+    pub amount_y: u64,
+
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
+    pub ref_code: Option<[u8; 20]>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
+    pub label: Option<[u8; 21]>,
+}
+
+/// Emitted when liquidity is removed from a pool
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RemoveLiquidityEvent {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub user: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_y: Pubkey,
+
+    pub amount_lp: u64,
+    pub amount_x: u64,
+    pub amount_y: u64,
+
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
+    pub label: Option<[u8; 21]>,
+}
+
+/// Emitted when a pool is initialized
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InitializePoolEvent {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub creator: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_mint_y: Pubkey,
+
+    pub amount_x: u64,
+    pub amount_y: u64,
+    pub amount_lp: u64,
+
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_utils::option_hex_bytes")
+    )]
+    pub label: Option<[u8; 21]>,
+}
+
+/// Darklake program event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DarklakeEvent {
+    Swap(SwapEvent),
+    Settle(SettleEvent),
+    Cancel(CancelEvent),
+    Slash(SlashEvent),
+    AddLiquidity(AddLiquidityEvent),
+    RemoveLiquidity(RemoveLiquidityEvent),
+    InitializePool(InitializePoolEvent),
+}
+
+/// Parse the Darklake events emitted in a transaction
+///
+/// Only `Program data:` lines logged while the Darklake program is the executing program are
+/// considered, events emitted by other programs (including CPIs made by Darklake) are skipped.
+///
+/// # Arguments
+/// * `logs` - The transaction log messages (`meta.log_messages`)
+///
+/// # Returns
+/// Returns the decoded events in emission order
+pub fn parse_events(logs: &[String]) -> Result<Vec<DarklakeEvent>> {
+    parse_events_with_program_id(logs, &DARKLAKE_PROGRAM_ID)
+}
+
+/// Parse the events emitted by the Darklake deployment at `program_id`, see `parse_events`
+///
+/// # Arguments
+/// * `logs` - The transaction log messages (`meta.log_messages`)
+/// * `program_id` - The Darklake program id, e.g. `DarklakeNetworkConfig::program_id`
+///
+/// # Returns
+/// Returns the decoded events in emission order
+pub fn parse_events_with_program_id(
+    logs: &[String],
+    program_id: &Pubkey,
+) -> Result<Vec<DarklakeEvent>> {
+    let darklake_program_id = program_id.to_string();
+
+    let mut events = vec![];
+    let mut invoke_stack: Vec<&str> = vec![];
+
+    for log in logs {
+        if let Some(encoded_data) = log.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if invoke_stack.last() != Some(&darklake_program_id.as_str()) {
+                continue;
+            }
+
+            let data = STANDARD
+                .decode(encoded_data.trim())
+                .context("Failed to decode program data")?;
+
+            if let Some(event) = decode_event(&data)? {
+                events.push(event);
+            }
+            continue;
+        }
+
+        // "Program <id> invoke [n]", "Program <id> success", "Program <id> failed: <err>"
+        let Some(program_log) = log.strip_prefix(PROGRAM_LOG_PREFIX) else {
+            continue;
+        };
+        let mut parts = program_log.split_whitespace();
+        let (Some(program_id), Some(action)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+
+        if action == "invoke" {
+            invoke_stack.push(program_id);
+        } else if action == "success" || action.starts_with("failed") {
+            invoke_stack.pop();
+        }
+    }
+
+    Ok(events)
+}
+
+/// Decode a single Darklake event from its raw bytes (discriminator followed by the event data)
+///
+/// # Returns
+/// Returns `None` if the discriminator does not belong to a known Darklake event
+pub fn decode_event(data: &[u8]) -> Result<Option<DarklakeEvent>> {
+    if data.len() < 8 {
+        return Ok(None);
+    }
+
+    let (discriminator, mut event_data) = data.split_at(8);
+    let discriminator: [u8; 8] = discriminator.try_into()?;
+
+    let event = match discriminator {
+        SWAP_EVENT_DISCRIMINATOR => DarklakeEvent::Swap(SwapEvent::deserialize(&mut event_data)?),
+        SETTLE_EVENT_DISCRIMINATOR => {
+            DarklakeEvent::Settle(SettleEvent::deserialize(&mut event_data)?)
+        }
+        CANCEL_EVENT_DISCRIMINATOR => {
+            DarklakeEvent::Cancel(CancelEvent::deserialize(&mut event_data)?)
+        }
+        SLASH_EVENT_DISCRIMINATOR => {
+            DarklakeEvent::Slash(SlashEvent::deserialize(&mut event_data)?)
+        }
+        ADD_LIQUIDITY_EVENT_DISCRIMINATOR => {
+            DarklakeEvent::AddLiquidity(AddLiquidityEvent::deserialize(&mut event_data)?)
+        }
+        REMOVE_LIQUIDITY_EVENT_DISCRIMINATOR => {
+            DarklakeEvent::RemoveLiquidity(RemoveLiquidityEvent::deserialize(&mut event_data)?)
+        }
+        INITIALIZE_POOL_EVENT_DISCRIMINATOR => {
+            DarklakeEvent::InitializePool(InitializePoolEvent::deserialize(&mut event_data)?)
+        }
+        _ => {
+            log::debug!("Skipping unknown event discriminator: {:?}", discriminator);
+            return Ok(None);
+        }
+    };
+
+    Ok(Some(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    /// Logs in the `meta.logMessages` format of `getTransaction`
+    ///
+    /// The fixtures are not chain captures yet, their `Program data` lines are encoded from the
+    /// `SwapEvent` and `SettleEvent` layouts in this module. Replace them with the logs of a mainnet
+    /// swap and settle of `DARKLAKE_PROGRAM_ID` and note the transaction signatures here.
+    fn load_logs(fixture: &str) -> Vec<String> {
+        serde_json::from_str(fixture).unwrap()
+    }
+
+    #[test]
+    fn test_parse_swap_event_logs() {
+        let logs = load_logs(include_str!("fixtures/swap_event_logs.json"));
+
+        let events = parse_events(&logs).unwrap();
+
+        // the token program "Program data" line in the fixture must be ignored
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            DarklakeEvent::Swap(swap) => {
+                assert_eq!(
+                    swap.trader,
+                    Pubkey::from_str("ECKUhGoz1bbJUFH3CQ6owx2D1wDfxfQXBHxzEzYJCg99").unwrap()
+                );
+                assert_eq!(
+                    swap.pool,
+                    Pubkey::from_str("3gLESRnfLgzAqu6PwGhBwsiBsnQ7BAtyWHhZ5zNcDPMF").unwrap()
+                );
+                assert_eq!(swap.actual_in, 1_000_000);
+                assert_eq!(swap.exchange_in, 998_000);
+                assert_eq!(swap.d_out, 495_000);
+                assert_eq!(swap.deadline, 123_456_789);
+                assert_eq!(swap.wsol_deposit, 10_000_000);
+                assert!(swap.is_x_to_y);
+                assert_eq!(&swap.label.unwrap()[..7], b"cv0.4.0");
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_settle_event_logs() {
+        let logs = load_logs(include_str!("fixtures/settle_event_logs.json"));
+
+        let events = parse_events(&logs).unwrap();
+
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            DarklakeEvent::Settle(settle) => {
+                assert_eq!(
+                    settle.caller,
+                    Pubkey::from_str("WbGBrW4Ea8YZeMupC76EssNUEpvEjjMW6xRWnTYmJBT").unwrap()
+                );
+                assert_eq!(
+                    settle.trader,
+                    Pubkey::from_str("ECKUhGoz1bbJUFH3CQ6owx2D1wDfxfQXBHxzEzYJCg99").unwrap()
+                );
+                assert_eq!(settle.actual_out, 495_000);
+                assert_eq!(settle.protocol_fee, 300);
+                assert_eq!(settle.lp_fee, 2_700);
+                assert!(!settle.unwrap_wsol);
+                assert_eq!(settle.ref_code, None);
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_event_round_trip() {
+        let event = AddLiquidityEvent {
+            user: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            token_mint_x: Pubkey::new_unique(),
+            token_mint_y: Pubkey::new_unique(),
+            amount_lp: 10,
+            amount_x: 20,
+            amount_y: 30,
+            ref_code: Some([1u8; 20]),
+            label: None,
+        };
+
+        let mut data = ADD_LIQUIDITY_EVENT_DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+
+        let logs = vec![
+            format!("Program {} invoke [1]", DARKLAKE_PROGRAM_ID),
+            format!("Program data: {}", STANDARD.encode(&data)),
+            format!("Program {} success", DARKLAKE_PROGRAM_ID),
+            // emitted outside of the Darklake program, must be skipped
+            format!("Program data: {}", STANDARD.encode(&data)),
+        ];
+
+        let events = parse_events(&logs).unwrap();
+
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            DarklakeEvent::AddLiquidity(add_liquidity) => {
+                assert_eq!(add_liquidity.user, event.user);
+                assert_eq!(add_liquidity.amount_lp, 10);
+                assert_eq!(add_liquidity.ref_code, Some([1u8; 20]));
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+
+        // a fork of the program only emits events for its own program id
+        let fork_program_id = Pubkey::new_unique();
+        assert!(
+            parse_events_with_program_id(&logs, &fork_program_id)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            parse_events_with_program_id(&logs, &DARKLAKE_PROGRAM_ID)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
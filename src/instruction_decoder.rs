@@ -0,0 +1,344 @@
+use anchor_lang::AnchorDeserialize;
+use anyhow::{Result, anyhow, bail};
+use solana_sdk::{
+    instruction::{CompiledInstruction, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    account_metas::{
+        DarklakeAmmAddLiquidity, DarklakeAmmCancel, DarklakeAmmInitializePool,
+        DarklakeAmmRemoveLiquidity, DarklakeAmmSettle, DarklakeAmmSlash, DarklakeAmmSwap,
+    },
+    amm::{
+        DarklakeAmmAddLiquidityParams, DarklakeAmmCancelParams, DarklakeAmmInitializePoolParams,
+        DarklakeAmmRemoveLiquidityParams, DarklakeAmmSettleParams, DarklakeAmmSlashParams,
+        DarklakeAmmSwapParams,
+    },
+    constants::{
+        ADD_LIQUIDITY_DISCRIMINATOR, CANCEL_DISCRIMINATOR, DARKLAKE_PROGRAM_ID,
+        INITIALIZE_POOL_DISCRIMINATOR, REMOVE_LIQUIDITY_DISCRIMINATOR, SETTLE_DISCRIMINATOR,
+        SLASH_DISCRIMINATOR, SWAP_DISCRIMINATOR,
+    },
+};
+
+/// Decoded Darklake program instruction
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DarklakeInstruction {
+    Swap {
+        params: DarklakeAmmSwapParams,
+        accounts: DarklakeAmmSwap,
+    },
+    Settle {
+        params: DarklakeAmmSettleParams,
+        accounts: DarklakeAmmSettle,
+    },
+    Cancel {
+        params: DarklakeAmmCancelParams,
+        accounts: DarklakeAmmCancel,
+    },
+    Slash {
+        params: DarklakeAmmSlashParams,
+        accounts: DarklakeAmmSlash,
+    },
+    AddLiquidity {
+        params: DarklakeAmmAddLiquidityParams,
+        accounts: DarklakeAmmAddLiquidity,
+    },
+    RemoveLiquidity {
+        params: DarklakeAmmRemoveLiquidityParams,
+        accounts: DarklakeAmmRemoveLiquidity,
+    },
+    InitializePool {
+        params: DarklakeAmmInitializePoolParams,
+        accounts: DarklakeAmmInitializePool,
+    },
+}
+
+/// Decode a Darklake instruction
+///
+/// # Arguments
+/// * `instruction` - The instruction to decode, its program id must be the Darklake program
+///
+/// # Returns
+/// Returns the typed instruction arguments together with the named accounts
+pub fn decode_instruction(instruction: &Instruction) -> Result<DarklakeInstruction> {
+    decode_instruction_with_program_id(instruction, &DARKLAKE_PROGRAM_ID)
+}
+
+/// Decode an instruction of the Darklake deployment at `program_id`, see `decode_instruction`
+///
+/// # Arguments
+/// * `instruction` - The instruction to decode, its program id must be `program_id`
+/// * `program_id` - The Darklake program id, e.g. `DarklakeNetworkConfig::program_id`
+///
+/// # Returns
+/// Returns the typed instruction arguments together with the named accounts
+pub fn decode_instruction_with_program_id(
+    instruction: &Instruction,
+    program_id: &Pubkey,
+) -> Result<DarklakeInstruction> {
+    if instruction.program_id != *program_id {
+        bail!(
+            "Not a Darklake instruction, program id: {}",
+            instruction.program_id
+        );
+    }
+
+    let account_keys: Vec<Pubkey> = instruction
+        .accounts
+        .iter()
+        .map(|account_meta| account_meta.pubkey)
+        .collect();
+
+    decode_instruction_data(&instruction.data, &account_keys)
+}
+
+/// Decode a Darklake compiled instruction taken from a transaction message
+///
+/// # Arguments
+/// * `instruction` - The compiled instruction to decode
+/// * `account_keys` - The message account keys the instruction indexes into (for v0 messages the
+///   static keys followed by the writable and readonly lookup table addresses)
+///
+/// # Returns
+/// Returns the typed instruction arguments together with the named accounts
+pub fn decode_compiled_instruction(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+) -> Result<DarklakeInstruction> {
+    decode_compiled_instruction_with_program_id(instruction, account_keys, &DARKLAKE_PROGRAM_ID)
+}
+
+/// Decode a compiled instruction of the Darklake deployment at `darklake_program_id`, see
+/// `decode_compiled_instruction`
+///
+/// # Arguments
+/// * `instruction` - The compiled instruction to decode
+/// * `account_keys` - The message account keys the instruction indexes into
+/// * `darklake_program_id` - The Darklake program id, e.g. `DarklakeNetworkConfig::program_id`
+///
+/// # Returns
+/// Returns the typed instruction arguments together with the named accounts
+pub fn decode_compiled_instruction_with_program_id(
+    instruction: &CompiledInstruction,
+    account_keys: &[Pubkey],
+    darklake_program_id: &Pubkey,
+) -> Result<DarklakeInstruction> {
+    let program_id = account_keys
+        .get(instruction.program_id_index as usize)
+        .ok_or_else(|| {
+            anyhow!(
+                "Program id index {} out of bounds",
+                instruction.program_id_index
+            )
+        })?;
+
+    if program_id != darklake_program_id {
+        bail!("Not a Darklake instruction, program id: {}", program_id);
+    }
+
+    let instruction_account_keys = instruction
+        .accounts
+        .iter()
+        .map(|index| {
+            account_keys
+                .get(*index as usize)
+                .copied()
+                .ok_or_else(|| anyhow!("Account index {} out of bounds", index))
+        })
+        .collect::<Result<Vec<Pubkey>>>()?;
+
+    decode_instruction_data(&instruction.data, &instruction_account_keys)
+}
+
+fn decode_instruction_data(data: &[u8], account_keys: &[Pubkey]) -> Result<DarklakeInstruction> {
+    if data.len() < 8 {
+        bail!("Instruction data is too short to contain a discriminator");
+    }
+
+    let (discriminator, mut args) = data.split_at(8);
+    let discriminator: [u8; 8] = discriminator.try_into()?;
+
+    let instruction = match discriminator {
+        SWAP_DISCRIMINATOR => DarklakeInstruction::Swap {
+            params: DarklakeAmmSwapParams::deserialize(&mut args)?,
+            accounts: DarklakeAmmSwap::try_from_account_keys(account_keys)?,
+        },
+        SETTLE_DISCRIMINATOR => DarklakeInstruction::Settle {
+            params: DarklakeAmmSettleParams::deserialize(&mut args)?,
+            accounts: DarklakeAmmSettle::try_from_account_keys(account_keys)?,
+        },
+        CANCEL_DISCRIMINATOR => DarklakeInstruction::Cancel {
+            params: DarklakeAmmCancelParams::deserialize(&mut args)?,
+            accounts: DarklakeAmmCancel::try_from_account_keys(account_keys)?,
+        },
+        SLASH_DISCRIMINATOR => DarklakeInstruction::Slash {
+            params: DarklakeAmmSlashParams::deserialize(&mut args)?,
+            accounts: DarklakeAmmSlash::try_from_account_keys(account_keys)?,
+        },
+        ADD_LIQUIDITY_DISCRIMINATOR => DarklakeInstruction::AddLiquidity {
+            params: DarklakeAmmAddLiquidityParams::deserialize(&mut args)?,
+            accounts: DarklakeAmmAddLiquidity::try_from_account_keys(account_keys)?,
+        },
+        REMOVE_LIQUIDITY_DISCRIMINATOR => DarklakeInstruction::RemoveLiquidity {
+            params: DarklakeAmmRemoveLiquidityParams::deserialize(&mut args)?,
+            accounts: DarklakeAmmRemoveLiquidity::try_from_account_keys(account_keys)?,
+        },
+        INITIALIZE_POOL_DISCRIMINATOR => DarklakeInstruction::InitializePool {
+            params: DarklakeAmmInitializePoolParams::deserialize(&mut args)?,
+            accounts: DarklakeAmmInitializePool::try_from_account_keys(account_keys)?,
+        },
+        _ => bail!(
+            "Unknown Darklake instruction discriminator: {:?}",
+            discriminator
+        ),
+    };
+
+    Ok(instruction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        amm::{Amm, RemoveLiquidityParams, SwapMode, SwapParams},
+        darklake_amm::{AmmConfig, DarklakeAmm, Pool},
+        network::DarklakeNetworkConfig,
+        token_extensions::MintExtensions,
+    };
+
+    fn test_amm() -> DarklakeAmm {
+        DarklakeAmm {
+            key: Pubkey::new_unique(),
+            pool: Pool {
+                amm_config: Pubkey::new_unique(),
+                token_mint_x: Pubkey::new_unique(),
+                token_mint_y: Pubkey::new_unique(),
+                reserve_x: Pubkey::new_unique(),
+                reserve_y: Pubkey::new_unique(),
+                ..Pool::default()
+            },
+            amm_config: AmmConfig::default(),
+            reserve_x_balance: 0,
+            reserve_y_balance: 0,
+            token_x_owner: spl_token::ID,
+            token_y_owner: spl_token_2022::ID,
+            token_x_transfer_fee_config: None,
+            token_y_transfer_fee_config: None,
+            token_x_extensions: MintExtensions::default(),
+            token_y_extensions: MintExtensions::default(),
+            network: DarklakeNetworkConfig::mainnet(),
+        }
+    }
+
+    #[test]
+    fn test_decode_swap_instruction() {
+        let amm = test_amm();
+        let user = Pubkey::new_unique();
+        let label = Some([7u8; 21]);
+
+        let swap = amm
+            .get_swap_and_account_metas(&SwapParams {
+                source_mint: amm.pool.token_mint_y,
+                destination_mint: amm.pool.token_mint_x,
+                token_transfer_authority: user,
+                amount_in: 1_000,
+                swap_mode: SwapMode::ExactIn,
+                min_out: 10,
+                salt: [1, 2, 3, 4, 5, 6, 7, 8],
+                label,
+            })
+            .unwrap();
+
+        let instruction = Instruction {
+            program_id: DARKLAKE_PROGRAM_ID,
+            accounts: swap.account_metas,
+            data: swap.data,
+        };
+
+        match decode_instruction(&instruction).unwrap() {
+            DarklakeInstruction::Swap { params, accounts } => {
+                assert_eq!(params.amount_in, 1_000);
+                assert!(!params.is_swap_x_to_y);
+                assert_eq!(params.c_min, swap.swap.c_min);
+                assert_eq!(params.label, label);
+                assert_eq!(accounts.user, user);
+                assert_eq!(accounts.pool, amm.key);
+                assert_eq!(accounts.order, amm.get_order_pubkey(&user).unwrap());
+                assert_eq!(accounts.token_mint_y_program, spl_token_2022::ID);
+            }
+            other => panic!("Unexpected instruction: {:?}", other),
+        }
+
+        let fork_program_id = Pubkey::new_unique();
+        assert!(decode_instruction_with_program_id(&instruction, &fork_program_id).is_err());
+        assert!(
+            decode_instruction_with_program_id(
+                &Instruction {
+                    program_id: fork_program_id,
+                    ..instruction
+                },
+                &fork_program_id
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_decode_compiled_remove_liquidity_instruction() {
+        let amm = test_amm();
+        let user = Pubkey::new_unique();
+
+        let remove_liquidity = amm
+            .get_remove_liquidity_and_account_metas(&RemoveLiquidityParams {
+                user,
+                amount_lp: 500,
+                min_amount_x: 1,
+                min_amount_y: 2,
+                label: None,
+            })
+            .unwrap();
+
+        // message keys are the instruction accounts in reverse followed by the program id
+        let mut account_keys: Vec<Pubkey> = remove_liquidity
+            .account_metas
+            .iter()
+            .rev()
+            .map(|account_meta| account_meta.pubkey)
+            .collect();
+        account_keys.push(DARKLAKE_PROGRAM_ID);
+
+        let accounts_len = remove_liquidity.account_metas.len();
+        let compiled_instruction = CompiledInstruction {
+            program_id_index: accounts_len as u8,
+            accounts: (0..accounts_len).rev().map(|index| index as u8).collect(),
+            data: remove_liquidity.data,
+        };
+
+        match decode_compiled_instruction(&compiled_instruction, &account_keys).unwrap() {
+            DarklakeInstruction::RemoveLiquidity { params, accounts } => {
+                assert_eq!(params.amount_lp, 500);
+                assert_eq!(params.min_amount_x, 1);
+                assert_eq!(params.min_amount_y, 2);
+                assert_eq!(params.label, None);
+                assert_eq!(accounts.user, user);
+                assert_eq!(accounts.amm_config, amm.pool.amm_config);
+                assert_eq!(accounts.pool, amm.key);
+            }
+            other => panic!("Unexpected instruction: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_foreign_program() {
+        let instruction = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![],
+            data: SWAP_DISCRIMINATOR.to_vec(),
+        };
+
+        assert!(decode_instruction(&instruction).is_err());
+    }
+}
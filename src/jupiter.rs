@@ -0,0 +1,310 @@
+use std::sync::atomic::Ordering;
+
+use anyhow::{Result, bail};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams, SwapAndAccountMetas,
+    SwapMode, SwapParams,
+};
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    account_metas::DarklakeAmmSwap,
+    amm::{self, AccountData, Amm as _},
+    darklake_amm::DarklakeAmm,
+    network::DarklakeNetworkConfig,
+};
+
+/// Darklake pool implementing `jupiter_amm_interface::Amm`
+///
+/// Quote-only: quotes are served from the aggregator's account map, the epoch for Token-2022
+/// transfer fees is read from the shared `ClockRef`. A Darklake swap commits an order that is
+/// settled with a proof in a second transaction, so neither step can be a single swap leg of a
+/// route and `get_swap_and_account_metas` always fails. Swaps must be built with
+/// `DarklakeSDK::swap_tx`/`swap_ix`.
+#[derive(Clone)]
+pub struct DarklakeJupiterAmm {
+    amm: DarklakeAmm,
+    clock_ref: ClockRef,
+}
+
+impl DarklakeJupiterAmm {
+    /// Load a pool of the given Darklake deployment, `from_keyed_account` assumes mainnet
+    pub fn from_keyed_account_with_network(
+        keyed_account: &KeyedAccount,
+        amm_context: &AmmContext,
+        network: DarklakeNetworkConfig,
+    ) -> Result<Self> {
+        let amm = DarklakeAmm::load_pool_with_network(
+            &amm::KeyedAccount {
+                key: keyed_account.key,
+                account: AccountData {
+                    data: keyed_account.account.data.clone(),
+                    owner: keyed_account.account.owner,
+                },
+            },
+            network,
+        )?;
+
+        Ok(Self {
+            amm,
+            clock_ref: amm_context.clock_ref.clone(),
+        })
+    }
+}
+
+impl Amm for DarklakeJupiterAmm {
+    fn from_keyed_account(keyed_account: &KeyedAccount, amm_context: &AmmContext) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_keyed_account_with_network(
+            keyed_account,
+            amm_context,
+            DarklakeNetworkConfig::mainnet(),
+        )
+    }
+
+    fn label(&self) -> String {
+        "Darklake".to_string()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        self.amm.network.program_id
+    }
+
+    fn key(&self) -> Pubkey {
+        self.amm.key()
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        self.amm.get_reserve_mints()
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        self.amm.get_accounts_to_update()
+    }
+
+    fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        let account_map = self
+            .amm
+            .get_accounts_to_update()
+            .into_iter()
+            .filter_map(|key| {
+                account_map.get(&key).map(|account| {
+                    (
+                        key,
+                        AccountData {
+                            data: account.data.clone(),
+                            owner: account.owner,
+                        },
+                    )
+                })
+            })
+            .collect::<amm::AccountMap>();
+
+        self.amm.update(&account_map)
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        if quote_params.swap_mode != SwapMode::ExactIn {
+            bail!("Exact out not supported");
+        }
+
+        let reserve_mints = self.amm.get_reserve_mints();
+        if !reserve_mints.contains(&quote_params.input_mint)
+            || !reserve_mints.contains(&quote_params.output_mint)
+            || quote_params.input_mint == quote_params.output_mint
+        {
+            bail!(
+                "Pool {} does not trade {} for {}",
+                self.amm.key(),
+                quote_params.input_mint,
+                quote_params.output_mint
+            );
+        }
+
+        let quote = self.amm.quote(&amm::QuoteParams {
+            input_mint: quote_params.input_mint,
+            amount: quote_params.amount,
+            swap_mode: amm::SwapMode::ExactIn,
+            epoch: self.clock_ref.epoch.load(Ordering::Relaxed),
+        })?;
+
+        Ok(Quote {
+            in_amount: quote.in_amount,
+            out_amount: quote.out_amount,
+            fee_amount: quote.fee_amount,
+            fee_mint: quote.fee_mint,
+            // jupiter expects the fee as a fraction
+            fee_pct: quote.fee_pct / Decimal::ONE_HUNDRED,
+        })
+    }
+
+    fn get_swap_and_account_metas(&self, _swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
+        // `jupiter_amm_interface::Swap` has no Darklake variant and a Darklake swap only commits
+        // the order, the output arrives with the settle transaction
+        bail!(
+            "Darklake swaps settle in a separate transaction and cannot be routed, use DarklakeSDK::swap_ix"
+        )
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn supports_exact_out(&self) -> bool {
+        self.amm.supports_exact_out()
+    }
+
+    fn get_accounts_len(&self) -> usize {
+        DarklakeAmmSwap::ACCOUNTS_LEN
+    }
+
+    fn is_active(&self) -> bool {
+        self.amm.is_active()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        darklake_pool::DarklakePool,
+        test_utils::{
+            pool_accounts, token_2022_account, transfer_fee_config, transfer_fee_mint_account,
+        },
+    };
+    use solana_sdk::account::Account;
+
+    fn jupiter_account_map(account_map: &amm::AccountMap) -> AccountMap {
+        account_map
+            .iter()
+            .map(|(key, account)| {
+                (
+                    *key,
+                    Account {
+                        lamports: 1_000_000,
+                        data: account.data.clone(),
+                        owner: account.owner,
+                        executable: false,
+                        rent_epoch: 0,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn load_jupiter_amm(
+        pool_key: &Pubkey,
+        account_map: &amm::AccountMap,
+        clock_ref: &ClockRef,
+    ) -> DarklakeJupiterAmm {
+        let keyed_account = KeyedAccount {
+            key: *pool_key,
+            account: jupiter_account_map(account_map).remove(pool_key).unwrap(),
+            params: None,
+        };
+        let mut jupiter_amm = DarklakeJupiterAmm::from_keyed_account(
+            &keyed_account,
+            &AmmContext {
+                clock_ref: clock_ref.clone(),
+            },
+        )
+        .unwrap();
+
+        // the aggregator passes accounts of every market, only the pool's are read
+        let mut jupiter_account_map = jupiter_account_map(account_map);
+        jupiter_account_map.insert(Pubkey::new_unique(), Account::default());
+        jupiter_amm.update(&jupiter_account_map).unwrap();
+
+        jupiter_amm
+    }
+
+    fn quote_params(input_mint: Pubkey, output_mint: Pubkey, amount: u64) -> QuoteParams {
+        QuoteParams {
+            amount,
+            input_mint,
+            output_mint,
+            swap_mode: SwapMode::ExactIn,
+        }
+    }
+
+    #[test]
+    fn test_quotes_like_darklake_pool() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool_key, pool, account_map) =
+            pool_accounts(token_a, token_b, 1_000_000_000, 2_000_000_000);
+
+        let jupiter_amm = load_jupiter_amm(&pool_key, &account_map, &ClockRef::default());
+        assert_eq!(jupiter_amm.key(), pool_key);
+        assert_eq!(jupiter_amm.program_id(), crate::DARKLAKE_PROGRAM_ID);
+        assert_eq!(
+            jupiter_amm.get_reserve_mints(),
+            vec![pool.token_mint_x, pool.token_mint_y]
+        );
+
+        let quote = jupiter_amm
+            .quote(&quote_params(
+                pool.token_mint_x,
+                pool.token_mint_y,
+                1_000_000,
+            ))
+            .unwrap();
+        let darklake_pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+        let darklake_quote = darklake_pool.quote(&pool.token_mint_x, 1_000_000).unwrap();
+
+        assert_eq!(quote.in_amount, darklake_quote.in_amount);
+        assert_eq!(quote.out_amount, darklake_quote.out_amount);
+        assert_eq!(quote.fee_amount, darklake_quote.fee_amount);
+        // 0.3% as a fraction
+        assert_eq!(quote.fee_pct, Decimal::new(3, 3));
+    }
+
+    #[test]
+    fn test_rejects_mints_outside_the_pool() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool_key, pool, account_map) =
+            pool_accounts(token_a, token_b, 1_000_000_000, 1_000_000_000);
+        let jupiter_amm = load_jupiter_amm(&pool_key, &account_map, &ClockRef::default());
+
+        for (input_mint, output_mint) in [
+            (pool.token_mint_x, pool.token_mint_x),
+            (pool.token_mint_x, Pubkey::new_unique()),
+            (Pubkey::new_unique(), pool.token_mint_y),
+        ] {
+            assert!(
+                jupiter_amm
+                    .quote(&quote_params(input_mint, output_mint, 1_000))
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_reads_transfer_fee_epoch_from_clock_ref() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (pool_key, pool, mut account_map) =
+            pool_accounts(token_a, token_b, 1_000_000_000, 1_000_000_000);
+
+        // y charges 1% until epoch 5 and 2% from then on
+        account_map.insert(
+            pool.token_mint_y,
+            transfer_fee_mint_account(transfer_fee_config(100, 200, 5)),
+        );
+        account_map.insert(
+            pool.reserve_y,
+            token_2022_account(pool.token_mint_y, 1_000_000_000),
+        );
+
+        let clock_ref = ClockRef::default();
+        let jupiter_amm = load_jupiter_amm(&pool_key, &account_map, &clock_ref);
+        let params = quote_params(pool.token_mint_x, pool.token_mint_y, 1_000_000);
+
+        let out_amount_before = jupiter_amm.quote(&params).unwrap().out_amount;
+        clock_ref.epoch.store(5, Ordering::Relaxed);
+        let out_amount_after = jupiter_amm.quote(&params).unwrap().out_amount;
+
+        assert!(out_amount_after < out_amount_before);
+    }
+}
@@ -133,12 +133,12 @@
 //!     .get_latest_blockhash()
 //!     .context("Failed to get recent blockhash")?;
 //!
-//! let address_lookup_table = get_address_lookup_table(&rpc_client, DEVNET_LOOKUP).await?;
+//! let address_lookup_tables = sdk.get_lookup_tables().await?;
 //!
 //! let message_v0 = v0::Message::try_compile(
 //!     &user_keypair.pubkey(),
 //!     &[swap_ix],
-//!     &[address_lookup_table.clone()],
+//!     &address_lookup_tables,
 //!     recent_blockhash,
 //! )?;
 //!
@@ -176,7 +176,7 @@
 //! let message_v0 = v0::Message::try_compile(
 //!     &user_keypair.pubkey(),
 //!     &[compute_budget_ix, finalize_ix],
-//!     &[address_lookup_table],
+//!     &address_lookup_tables,
 //!     recent_blockhash,
 //! )?;
 //!
@@ -199,7 +199,7 @@
 //! **Parameters:**
 //! - `rpc_endpoint: &str` - Solana RPC endpoint URL
 //! - `commitment_level: CommitmentLevel` - Commitment level for RPC calls
-//! - `is_devnet: bool` - Whether using devnet. Currently only devnet/mainnet supported.
+//! - `is_devnet: bool` - Whether using devnet, selects the devnet or mainnet `DarklakeNetworkConfig` preset. Use `new_with_network` for other deployments.
 //! - `label: Option<&str>` - Optional application/user label (max 10 characters). For example `Some("duck-ag")`.
 //! - `ref_code: Option<&str>` - Optional referral code (max 20 characters)
 //!
@@ -216,24 +216,235 @@
 //! )?;
 //! ```
 //!
+//! #### `DarklakeSDK::new_with_network(rpc_endpoint, commitment_level, network, label, ref_code)`
+//!
+//! Same as `new`, for the deployment described by a `DarklakeNetworkConfig`: the program id, address lookup table, pool creation fee vault, metadata program and default USDC route mint. Every PDA (authority, AmmConfig, pool, reserves, LP mint, orders) and every account owner check uses its program id.
+//!
+//! - **`DarklakeNetworkConfig::mainnet()`** / **`DarklakeNetworkConfig::devnet()`** - The presets selected by `is_devnet` in `new`
+//! - **`DarklakeNetworkConfig::custom(program_id, lookup_table, create_pool_fee_vault)`** - A fork of the program, e.g. on a local validator. The metadata program and USDC mint default to mainnet and can be overridden through the public fields
+//! - **`network()`** - The `DarklakeNetworkConfig` the SDK uses
+//!
+//! ```rust
+//! let network = DarklakeNetworkConfig::custom(fork_program_id, fork_lookup_table, fork_fee_vault);
+//! let sdk = DarklakeSDK::new_with_network(
+//!     "http://127.0.0.1:8899",
+//!     CommitmentLevel::Confirmed,
+//!     network,
+//!     None, // label
+//!     None, // ref_code
+//! )?;
+//! ```
+//!
+//! #### `DarklakeSDK::builder()` / `DarklakeSDKBuilder`
+//!
+//! Builds an SDK around a caller supplied RPC client instead of a URL, e.g. to share a connection pool, add auth headers or custom timeouts, or use a mock sender in tests. At most one RPC source can be set, and one is required unless a chain reader is set.
+//!
+//! - **`rpc_endpoint(url)`** / **`rpc_client(Arc<RpcClient>)`** / **`rpc_sender(sender)`** - The RPC source. A shared client keeps its own commitment level, the other two use `commitment(level)` (confirmed by default)
+//! - **`chain_reader(Arc<dyn ChainReader>)`** - Read chain state through a custom `ChainReader` instead of the RPC source, see below
+//! - **`network(network)`** - The `DarklakeNetworkConfig` (mainnet by default)
+//! - **`label(label)`** / **`ref_code(ref_code)`** - Same limits as in `new`
+//! - **`circuits_dir(dir)`** - Load the settle and cancel circuits from another directory
+//! - **`amm_config_index(index)`** / **`slash_expired_orders(bool)`** - Same as the `set_` methods
+//! - **`lookup_table_ttl(Duration)`** - How long fetched address lookup tables are reused (5 minutes by default, `Duration::ZERO` fetches them for every transaction)
+//! - **`extra_lookup_tables(Vec<Pubkey>)`** - Your own lookup tables, compiled into every transaction next to the network's
+//! - **`durable_nonce(DurableNonceConfig { nonce_account, nonce_authority })`** - Build every `_tx` transaction with a durable nonce, see below
+//! - **`tx_defaults(TxDefaults { swap_compute_unit_limit, compute_unit_limit, compute_unit_price })`** - Compute unit limits of the `_tx` functions (300,000 for swaps, 500,000 for settle, cancel, slash and pool initialization) and an optional priority fee in micro-lamports added to every transaction
+//!
+//! ```rust
+//! let sdk = DarklakeSDK::builder()
+//!     .rpc_client(shared_rpc_client.clone())
+//!     .network(DarklakeNetworkConfig::devnet())
+//!     .label("duck-ag")
+//!     .tx_defaults(TxDefaults {
+//!         compute_unit_price: Some(10_000),
+//!         ..TxDefaults::default()
+//!     })
+//!     .build()?;
+//! ```
+//!
+//! #### `ChainReader`
+//!
+//! Every account, slot, epoch, blockhash and lookup table read of the SDK goes through the `ChainReader` trait. `RpcChainReader` wraps the RPC client and is used by default. `InMemoryChainReader` serves accounts from memory so the `_tx`, `_ix` and quote functions run offline, e.g. in unit tests. Without an RPC source, `execute_route` and `execute_zap` fail because they cannot send transactions.
+//!
+//! - **`InMemoryChainReader::from_fixture_dir(dir)`** / **`load_fixture(path)`** - Load accounts saved with `solana account <ADDRESS> --output json`
+//! - **`set_account(pubkey, account)`** / **`set_account_map(&account_map)`** / **`remove_account(&pubkey)`** - Change accounts at any time, also after the reader was passed to the builder
+//! - **`set_slot(slot)`** / **`set_epoch(epoch)`** / **`set_blockhash(hash)`** - Zero by default
+//! - Rent is computed with the default rent parameters
+//!
+//! ```rust
+//! let chain_reader = Arc::new(InMemoryChainReader::from_fixture_dir("tests/fixtures")?);
+//! chain_reader.set_slot(350_000_000);
+//!
+//! let mut sdk = DarklakeSDK::builder()
+//!     .chain_reader(chain_reader.clone())
+//!     .build()?;
+//!
+//! let (swap_tx, order_key, min_out, salt) = sdk
+//!     .swap_tx(&token_in, &token_out, 1_000, 1, &user_keypair.pubkey())
+//!     .await?;
+//! ```
+//!
 //! ### DarklakeSDK Methods
 //!
 //! #### Transaction Functions (`_tx`) - Fully Formatted Transactions
 //!
-//! - **`quote(&token_in, &token_out, amount_in)`** - Get a quote for a swap
+//! - **`quote(&token_in, &token_out, amount_in)`** - Get a quote for a swap. **Breaking change:** `fee_pct` is now a percentage (`0.3` for a 0.3% fee), versions up to 0.5.0 returned the raw `trade_fee_rate` (`3000`). Read `amm_config.trade_fee_rate` if you need the raw rate. Fails with `QuoteError::OutputIsZero` when nothing is left after the fees.
+//! - **`quote_add_liquidity(&token_x, &token_y, &token, amount)`** - Get the LP tokens minted and the amounts of both tokens required to deposit `amount` of `token`
+//! - **`quote_remove_liquidity(&token_x, &token_y, amount_lp)`** - Get the amounts of both tokens returned for burning `amount_lp`, excluding locked amounts and fees
+//! - **`get_lp_position(&user, &token_x, &token_y)`** - Get the user's LP balance, pool share, underlying token amounts and share of accumulated LP fees (`LpPosition`)
 //! - **`swap_tx(&token_in, &token_out, amount_in, min_amount_out, &token_owner)`** - Generate swap transaction, returns `(VersionedTransaction, order_key, min_amount_out, salt)`
+//! - **`swap_tx_with_slippage(&token_in, &token_out, amount_in, slippage_bps, &token_owner)`** - Quote, derive `min_out` from the slippage tolerance and generate the swap transaction, returns `(VersionedTransaction, order_key, min_amount_out, salt, quote)`
 //! - **`finalize_tx(&order_key, unwrap_wsol, min_out, salt, settle_signer)`** - Generate finalize transaction using parameters from swap_tx
+//! - **`settle_tx(&order_key, unwrap_wsol, min_out, salt, settle_signer)`** - Generate settle transaction, fails if the order cannot be settled
+//! - **`cancel_tx(&order_key, min_out, salt, settle_signer)`** - Generate cancel transaction, fails if the order cannot be cancelled
+//! - **`slash_tx(&order_key, settle_signer)`** - Generate slash transaction for an expired order
+//!
+//! #### Transaction Plans (`_plan`) - Composable Instructions
+//!
+//! Every `_tx` function has a `_plan` counterpart with the same arguments (`swap_plan`, `finalize_plan`, `settle_plan`, `cancel_plan`, `slash_plan`, `add_liquidity_plan`, `remove_liquidity_plan`, `initialize_pool_plan`) that returns a `DarklakeTxPlan` instead of a compiled transaction:
+//!
+//! - **`compute_budget_instructions`** - Compute unit limit and price from `TxDefaults`, replace them with your own when merging
+//! - **`setup_instructions`** - WSOL wrapping, token account creation and the slash of an expired order, run before the core instruction
+//! - **`core_instruction`** - The Darklake program instruction
+//! - **`cleanup_instructions`** - WSOL account closing, run after the core instruction
+//! - **`lookup_tables`** - The network's lookup table and the extra lookup tables
+//! - **`signers`** - Every account that has to sign, payer first
+//! - **`instructions()`** / **`compile(recent_blockhash)`** - All instructions in execution order, or the plan compiled as is
+//!
+//! ```rust
+//! let (plan, order_key, min_out, salt) = sdk
+//!     .swap_plan(&token_in, &token_out, 1_000, 1, &user_keypair.pubkey())
+//!     .await?;
+//!
+//! let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(400_000)];
+//! instructions.extend(my_setup_instructions);
+//! instructions.extend(plan.setup_instructions);
+//! instructions.push(plan.core_instruction);
+//! instructions.extend(plan.cleanup_instructions);
+//!
+//! let message_v0 = v0::Message::try_compile(
+//!     &user_keypair.pubkey(),
+//!     &instructions,
+//!     &plan.lookup_tables,
+//!     recent_blockhash,
+//! )?;
+//! ```
 //!
 //! #### Instruction Functions (`_ix`) - Core Instructions
 //!
 //! - **`swap_ix(&swap_params)`** - Generate swap instruction
-//! - **`finalize_ix(&finalize_params)`** - Generate finalize instruction
+//! - **`finalize_ix(&finalize_params)`** - Generate finalize instruction (settle, cancel or slash picked from the order state)
+//! - **`settle_ix(&settle_params)`** - Generate settle instruction
+//! - **`cancel_ix(&cancel_params)`** - Generate cancel instruction
+//! - **`slash_ix(&slash_params)`** - Generate slash instruction
+//!
+//! #### Multi-hop Routing and Zaps
+//!
+//! - **`find_route(&token_in, &token_out, amount_in, intermediate_mints)`** - Quote the direct pool and every path through one intermediate mint (WSOL and USDC by default) and return the `Route` with the best output
+//! - **`execute_route(&route, slippage_bps, &signer)`** - Sign and send each hop sequentially (swap then settle), feeding the settled order's `actual_out` (less the intermediate token's transfer fee) into the next hop. A hop that cannot be settled is cancelled and execution stops. Routes are limited to a single intermediate mint
+//! - **`quote_zap(&token_in, &token_other, amount_in)`** - Split a single token deposit into the part to swap and the expected liquidity deposit (`ZapQuote`)
+//! - **`execute_zap(&token_in, &token_other, amount_in, slippage_bps, &signer)`** - Sign and send a single token deposit: swap, settle (or cancel and stop), then add liquidity with the remaining input and the settled order's `actual_out` less the transfer fee
+//!
+//! #### Fee Tiers
+//!
+//! Pools are derived from their AmmConfig (fee tier) and the mint pair, so a pair can have one pool per AmmConfig. The SDK uses the AmmConfig with index `DEFAULT_AMM_CONFIG_INDEX` (0) for pool derivation, pool initialization, routing and order lookups unless another one is set.
+//!
+//! - **`list_amm_configs()`** - List every AmmConfig with its address and fee rates (`AmmConfigInfo`)
+//! - **`list_pair_pools(&token_x, &token_y)`** - List the pools of a mint pair across every AmmConfig, sorted by trade fee rate
+//! - **`set_amm_config_index(index)`** / **`set_amm_config(amm_config)`** - Select the AmmConfig by index or address
+//! - **`amm_config()`** / **`get_amm_config_address(index)`** - The selected AmmConfig address and the address of an index
+//!
+//! #### Address Lookup Tables
+//!
+//! The `_tx` functions compile their transactions with the network's lookup table (`DEVNET_LOOKUP`/`MAINNET_LOOKUP`) and the extra lookup tables. Fetched tables are cached for the builder's `lookup_table_ttl`. When compilation fails, e.g. because a cached table misses recently added addresses and the transaction is too large, the tables are fetched again and the transaction is compiled once more. Sending a transaction that fails on a lookup table also clears the cache.
+//!
+//! - **`get_lookup_tables()`** - The cached `AddressLookupTableAccount`s, network table first, to compile your own transactions with the `_ix` functions
+//! - **`set_extra_lookup_tables(lookup_tables)`** - Replace the extra lookup tables
+//! - **`invalidate_lookup_tables()`** - Drop the cache, e.g. after extending one of your tables
+//!
+//! #### Durable Nonces
+//!
+//! Transactions built from a recent blockhash expire after ~150 slots. For flows where signing takes longer (multi-party approval, offline signing), the `_tx` functions can use a durable nonce account instead: the transaction starts with an `AdvanceNonceAccount` instruction and uses the stored nonce as blockhash, so it stays valid until the nonce is advanced. The nonce authority has to sign every transaction, and only one transaction can be sent per nonce value. `_plan` functions and `DarklakeTxPlan::compile` are not affected.
+//!
+//! - **`set_durable_nonce(Some(DurableNonceConfig { nonce_account, nonce_authority }))`** / **`durable_nonce()`** - Use a nonce account for every `_tx` function, `None` goes back to recent blockhashes
+//! - **`get_nonce_data(&nonce_account)`** - The nonce authority, the current nonce (`blockhash()`) and the fee per signature
+//! - **`create_nonce_account_ix(&payer, &nonce_account, &nonce_authority)`** / **`create_nonce_account_tx(...)`** - Create and initialize a rent-exempt nonce account, the nonce account keypair has to sign
+//!
+//! ```rust
+//! let nonce_keypair = Keypair::new();
+//! let create_nonce_tx = sdk
+//!     .create_nonce_account_tx(&payer.pubkey(), &nonce_keypair.pubkey(), &approver)
+//!     .await?;
+//!
+//! sdk.set_durable_nonce(Some(DurableNonceConfig {
+//!     nonce_account: nonce_keypair.pubkey(),
+//!     nonce_authority: approver,
+//! }));
+//!
+//! // valid until the nonce is advanced, signed by the user and the approver
+//! let (swap_tx, order_key, min_out, salt) = sdk
+//!     .swap_tx(&token_in, &token_out, 1_000, 1, &user_keypair.pubkey())
+//!     .await?;
+//! ```
+//!
+//! #### Preflight Checks
+//!
+//! `swap_ix` and `swap_tx` fail with a `SwapPreflightError` before anything is sent when the pool is halted (`PoolHalted`) or the trader already has an open order in the pool (`OrderExists`). Orders are derived from the pool and the trader, so only one can be open at a time and it must be finalized first.
+//!
+//! - **`set_slash_expired_orders(true)`** - Let `swap_tx` slash the trader's expired order in the same transaction instead of failing
+//! - **`preflight_swap(&token_in, &token_out, amount_in, &user)`** - Check the user's source token account and SOL balance (SOL input, `wsol_trade_deposit`, order rent) before building a swap
+//! - **`preflight_add_liquidity(&token_x, &token_y, max_amount_x, max_amount_y, &user)`** - Check the user's x, y and LP token accounts and SOL balance before building a deposit
+//!
+//! The preflight functions return a list of `PreflightIssue`s (`MissingTokenAccount`, `InsufficientTokenBalance`, `InsufficientSol`), empty when the transaction can be funded. Transaction fees are not included.
 //!
 //! #### Internal State Management
 //!
 //! - **`load_pool(&token_x, &token_y)`** - Load pool data for internal state tracking
 //! - **`update_accounts()`** - Update internal state with latest chain data
 //! - **`get_order(&user, commitment_level)`** - Get order data (bypasses internal cache, fetches latest state directly from chain)
+//! - **`list_pools()`** - List every Darklake pool via `getProgramAccounts` with its address, mints, reserve balances, LP supply, locked amounts and fee balances (`PoolInfo`)
+//! - **`pool_status(&token_x, &token_y)`** - Get whether a pool is halted with its reserve balances and the reserves available to trade (`PoolStatus`)
+//!
+//! #### Offline Quoting
+//!
+//! - **`DarklakePool::required_accounts(&pool_key, &pool)`** - Accounts needed to build a pool (pool, mints, reserves, AmmConfig)
+//! - **`DarklakePool::from_account_map(&pool_key, &account_map, epoch)`** - Build an RPC-free pool from raw account data (`AccountMap` of `AccountData { data, owner }`)
+//! - **`DarklakePool::from_account_map_with_network(&pool_key, &account_map, epoch, network)`** - Same as above for the pool of a custom deployment
+//! - **`update(&account_map)`** / **`set_epoch(epoch)`** - Refresh the pool from newer account data or a new epoch
+//! - **`quote(&token_in, amount_in)`** - Get a quote without any RPC call
+//! - **`quote_add_liquidity(&token, amount)`** / **`quote_remove_liquidity(amount_lp)`** - Liquidity quotes without any RPC call
+//! - **`lp_position(amount_lp)`** - Value an LP balance without any RPC call
+//! - **`quote_zap(&token_in, amount_in)`** - Zap quote without any RPC call
+//! - **`status()`** - The pool's `PoolStatus` without any RPC call
+//!
+//! #### Token-2022 Extensions
+//!
+//! - **`get_mint_extensions(&token_x, &token_y)`** / **`DarklakePool::mint_extensions()`** - The `MintExtensions` of both pool mints (transfer fee, transfer hook, non-transferable, confidential transfer, default account state, permanent delegate)
+//! - **`MintExtensions::warnings()`** - Human readable notes for supported extensions users should know about (frozen default account state, permanent delegate, confidential transfer, transfer hook)
+//!
+//! Transfer fees are read from the `TransferFeeConfig` of the mints. The older fee applies until the epoch of the newer fee (the current epoch for `DarklakeSDK::quote`, the pool's `epoch` for `DarklakePool`), and quotes deduct the fee on both legs (`input_transfer_fee`, `output_transfer_fee`).
+//!
+//! Pools with a non-transferable mint fail to load. For transfer hook mints, `swap_ix`, `settle_ix`, `cancel_ix`, `slash_ix`, `add_liquidity_ix` and `remove_liquidity_ix` (and the `_tx` functions built on them) append the hook program, its validation account and the extra accounts it requires. The finalize instructions read the order to resolve only the token that actually leaves the pool.
+//!
+//! #### Instruction Decoding
+//!
+//! - **`decode_instruction(&instruction)`** - Decode a Darklake `Instruction` into a `DarklakeInstruction` with typed arguments and named accounts
+//! - **`decode_compiled_instruction(&compiled_instruction, &account_keys)`** - Same as above for a `CompiledInstruction` taken from a transaction message
+//! - **`decode_instruction_with_program_id(&instruction, &program_id)`** / **`decode_compiled_instruction_with_program_id(&compiled_instruction, &account_keys, &program_id)`** - Same as above for another deployment, e.g. `&network.program_id`
+//!
+//! #### Event Parsing
+//!
+//! - **`parse_events(&logs)`** - Parse the `DarklakeEvent`s (swap, settle, cancel, slash, add/remove liquidity, pool initialized) emitted in a transaction from its log messages
+//! - **`parse_events_with_program_id(&logs, &program_id)`** - Same as above for another deployment, e.g. `&network.program_id`
+//! - **`decode_event(&data)`** - Decode a single event from its raw bytes
+//!
+//! #### Account Decoding
+//!
+//! - **`Pool::try_from_account_data(&data, &owner)`** - Decode a pool account
+//! - **`AmmConfig::try_from_account_data(&data, &owner)`** - Decode an AMM config account
+//! - **`Order::try_from_account_data(&data, &owner)`** - Decode an order account
+//!
+//! All decoders verify the account owner (`DARKLAKE_PROGRAM_ID`), the Anchor discriminator and the data length and return an `AccountDecodeError` on mismatch. `try_from_program_account_data(&data, &owner, &program_id)` does the same for accounts of a custom deployment.
 //!
 //! ### Parameter Types
 //!
@@ -265,6 +476,26 @@
 //! }
 //! ```
 //!
+//! ### Serde
+//!
+//! Enable the optional `serde` feature to derive `Serialize`/`Deserialize` for the parameter (`*ParamsIx`), quote, account state (`Pool`, `AmmConfig`, `Order`, `AccountData`), event and decoded instruction (`DarklakeInstruction` and its account structs) types. Pubkeys are (de)serialized as base58 strings and byte arrays (salts, commitments, proofs, labels, account data) as hex strings.
+//!
+//! ```toml
+//! [dependencies]
+//! darklake-sdk-on-chain = { version = "0.5.0", features = ["serde"] }
+//! ```
+//!
+//! ### Jupiter
+//!
+//! Enable the optional `jupiter` feature to get `DarklakeJupiterAmm`, a quote-only implementation of `jupiter_amm_interface::Amm` for Darklake pools. It loads from the pool `KeyedAccount`, updates from the aggregator's account map and quotes using the epoch from the shared `ClockRef`. `from_keyed_account` assumes the mainnet program, use `DarklakeJupiterAmm::from_keyed_account_with_network(&keyed_account, &amm_context, network)` for another deployment.
+//!
+//! ```toml
+//! [dependencies]
+//! darklake-sdk-on-chain = { version = "0.5.0", features = ["jupiter"] }
+//! ```
+//!
+//! **The adapter is quote-only.** A Darklake swap is two transactions: the swap commits an order and locks the input, and a second transaction settles (or cancels) it with a zero-knowledge proof generated off chain. Neither step is a complete swap that an aggregator could place as one leg of a route, so `get_swap_and_account_metas` always returns an error. Use the adapter to price Darklake pools and build the swap itself with `swap_tx`/`swap_ix` and `finalize_tx`.
+//!
 //! ## 🌐 Network Configuration
 //!
 //! SDK needs an rpc url which is used for on chain data fetching.
@@ -300,22 +531,81 @@
 
 mod account_metas;
 mod amm;
+mod builder;
+mod chain_reader;
 mod constants;
 mod darklake_amm;
+mod darklake_pool;
+mod error;
+mod events;
+mod instruction_decoder;
+#[cfg(feature = "jupiter")]
+mod jupiter;
+mod lookup_table_cache;
+mod network;
+mod nonce;
 mod proof;
 mod reduced_amm_params;
+mod route;
 mod sdk;
+#[cfg(feature = "serde")]
+mod serde_utils;
+#[cfg(test)]
+mod test_utils;
+mod token_extensions;
+mod tx_plan;
 mod utils;
 
 pub use sdk::DarklakeSDK;
 
+pub use builder::{DarklakeSDKBuilder, TxDefaults};
+
+pub use chain_reader::{ChainReader, InMemoryChainReader, RpcChainReader};
+
 pub use reduced_amm_params::{
-    AddLiquidityParamsIx, FinalizeParamsIx, InitializePoolParamsIx, RemoveLiquidityParamsIx,
-    SwapParamsIx,
+    AddLiquidityParamsIx, CancelParamsIx, FinalizeParamsIx, InitializePoolParamsIx,
+    RemoveLiquidityParamsIx, SettleParamsIx, SlashParamsIx, SwapParamsIx,
+};
+
+pub use darklake_amm::{AmmConfig, Order, Pool};
+
+pub use darklake_pool::DarklakePool;
+
+pub use network::DarklakeNetworkConfig;
+
+pub use nonce::DurableNonceConfig;
+
+pub use route::{Route, RouteHop};
+
+pub use token_extensions::MintExtensions;
+
+pub use tx_plan::DarklakeTxPlan;
+
+#[cfg(feature = "jupiter")]
+pub use jupiter::DarklakeJupiterAmm;
+
+pub use error::{AccountDecodeError, QuoteError, SwapPreflightError};
+
+pub use amm::{
+    AccountData, AccountMap, AmmConfigInfo, DarklakeAmmAddLiquidityParams, DarklakeAmmCancelParams,
+    DarklakeAmmInitializePoolParams, DarklakeAmmRemoveLiquidityParams, DarklakeAmmSettleParams,
+    DarklakeAmmSlashParams, DarklakeAmmSwapParams, LiquidityQuote, LpPosition, PoolInfo,
+    PoolStatus, PreflightIssue, Quote, SwapMode, ZapQuote,
+};
+
+pub use account_metas::{
+    DarklakeAmmAddLiquidity, DarklakeAmmCancel, DarklakeAmmInitializePool,
+    DarklakeAmmRemoveLiquidity, DarklakeAmmSettle, DarklakeAmmSlash, DarklakeAmmSwap,
 };
 
-pub use darklake_amm::Order;
+pub use instruction_decoder::{
+    DarklakeInstruction, decode_compiled_instruction, decode_compiled_instruction_with_program_id,
+    decode_instruction, decode_instruction_with_program_id,
+};
 
-pub use amm::SwapMode;
+pub use events::{
+    AddLiquidityEvent, CancelEvent, DarklakeEvent, InitializePoolEvent, RemoveLiquidityEvent,
+    SettleEvent, SlashEvent, SwapEvent, decode_event, parse_events, parse_events_with_program_id,
+};
 
-pub use constants::{DEVNET_LOOKUP, MAINNET_LOOKUP};
+pub use constants::{DARKLAKE_PROGRAM_ID, DEFAULT_AMM_CONFIG_INDEX, DEVNET_LOOKUP, MAINNET_LOOKUP};
@@ -0,0 +1,123 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use solana_sdk::{address_lookup_table::AddressLookupTableAccount, pubkey::Pubkey};
+
+use crate::chain_reader::ChainReader;
+
+/// Default time a fetched address lookup table is reused before it is fetched again
+pub(crate) const DEFAULT_LOOKUP_TABLE_TTL: Duration = Duration::from_secs(300);
+
+/// Address lookup tables fetched by the SDK, reused until they are older than the TTL
+///
+/// Lookup tables are append-only while active, so a stale table only misses addresses added since
+/// it was fetched. A zero TTL disables caching.
+pub(crate) struct LookupTableCache {
+    ttl: Duration,
+    lookup_tables: Mutex<HashMap<Pubkey, (AddressLookupTableAccount, Instant)>>,
+}
+
+impl LookupTableCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            lookup_tables: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cached lookup tables, fetching the missing and expired ones
+    ///
+    /// # Returns
+    /// Returns the lookup tables in the order of `lookup_table_keys`
+    pub(crate) async fn get(
+        &self,
+        chain: &dyn ChainReader,
+        lookup_table_keys: &[Pubkey],
+    ) -> Result<Vec<AddressLookupTableAccount>> {
+        let mut lookup_tables = Vec::with_capacity(lookup_table_keys.len());
+
+        for lookup_table_key in lookup_table_keys {
+            let cached = self
+                .lookup_tables
+                .lock()
+                .unwrap()
+                .get(lookup_table_key)
+                .filter(|(_, fetched_at)| fetched_at.elapsed() < self.ttl)
+                .map(|(lookup_table, _)| lookup_table.clone());
+
+            let lookup_table = match cached {
+                Some(lookup_table) => lookup_table,
+                None => {
+                    let lookup_table = chain.get_address_lookup_table(lookup_table_key).await?;
+                    self.lookup_tables
+                        .lock()
+                        .unwrap()
+                        .insert(*lookup_table_key, (lookup_table.clone(), Instant::now()));
+                    lookup_table
+                }
+            };
+
+            lookup_tables.push(lookup_table);
+        }
+
+        Ok(lookup_tables)
+    }
+
+    /// Drop every cached lookup table so the next `get` fetches them again
+    pub(crate) fn invalidate(&self) {
+        self.lookup_tables.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        amm::AccountMap, chain_reader::InMemoryChainReader, test_utils::lookup_table_account,
+    };
+
+    #[tokio::test]
+    async fn test_reuses_lookup_table_until_invalidated() {
+        let chain_reader = InMemoryChainReader::new();
+        let lookup_table_key = Pubkey::new_unique();
+        let addresses = vec![Pubkey::new_unique()];
+        chain_reader.set_account_map(&AccountMap::from([(
+            lookup_table_key,
+            lookup_table_account(addresses.clone()),
+        )]));
+
+        let cache = LookupTableCache::new(DEFAULT_LOOKUP_TABLE_TTL);
+        let lookup_tables = cache.get(&chain_reader, &[lookup_table_key]).await.unwrap();
+        assert_eq!(lookup_tables[0].addresses, addresses);
+
+        let extended_addresses = vec![addresses[0], Pubkey::new_unique()];
+        chain_reader.set_account_map(&AccountMap::from([(
+            lookup_table_key,
+            lookup_table_account(extended_addresses.clone()),
+        )]));
+
+        let lookup_tables = cache.get(&chain_reader, &[lookup_table_key]).await.unwrap();
+        assert_eq!(lookup_tables[0].addresses, addresses);
+
+        cache.invalidate();
+        let lookup_tables = cache.get(&chain_reader, &[lookup_table_key]).await.unwrap();
+        assert_eq!(lookup_tables[0].addresses, extended_addresses);
+
+        let uncached = LookupTableCache::new(Duration::ZERO);
+        uncached
+            .get(&chain_reader, &[lookup_table_key])
+            .await
+            .unwrap();
+        chain_reader.remove_account(&lookup_table_key);
+        assert!(
+            uncached
+                .get(&chain_reader, &[lookup_table_key])
+                .await
+                .is_err()
+        );
+    }
+}
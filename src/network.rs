@@ -0,0 +1,131 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::constants::{
+    AMM_CONFIG_SEED, AUTHORITY_SEED, DARKLAKE_PROGRAM_ID, DEVNET_CREATE_POOL_FEE_VAULT,
+    DEVNET_LOOKUP, DEVNET_USDC_MINT, MAINNET_CREATE_POOL_FEE_VAULT, MAINNET_LOOKUP,
+    METADATA_PROGRAM_ID, USDC_MINT,
+};
+
+/// Addresses of a Darklake deployment
+///
+/// Every PDA is derived from `program_id`, so pointing the SDK at a fork of the program (e.g. on
+/// a local validator) only requires a custom config with the fork's addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DarklakeNetworkConfig {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub program_id: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub lookup_table: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub create_pool_fee_vault: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub metadata_program_id: Pubkey,
+    /// Default intermediate mint for routes, next to WSOL
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub usdc_mint: Pubkey,
+}
+
+impl DarklakeNetworkConfig {
+    /// The mainnet deployment
+    pub fn mainnet() -> Self {
+        Self {
+            program_id: DARKLAKE_PROGRAM_ID,
+            lookup_table: MAINNET_LOOKUP,
+            create_pool_fee_vault: MAINNET_CREATE_POOL_FEE_VAULT,
+            metadata_program_id: METADATA_PROGRAM_ID,
+            usdc_mint: USDC_MINT,
+        }
+    }
+
+    /// The devnet deployment
+    pub fn devnet() -> Self {
+        Self {
+            program_id: DARKLAKE_PROGRAM_ID,
+            lookup_table: DEVNET_LOOKUP,
+            create_pool_fee_vault: DEVNET_CREATE_POOL_FEE_VAULT,
+            metadata_program_id: METADATA_PROGRAM_ID,
+            usdc_mint: DEVNET_USDC_MINT,
+        }
+    }
+
+    /// Mainnet or devnet preset
+    pub fn from_is_devnet(is_devnet: bool) -> Self {
+        if is_devnet {
+            Self::devnet()
+        } else {
+            Self::mainnet()
+        }
+    }
+
+    /// A custom deployment, e.g. a fork of the program on a local validator
+    ///
+    /// The metadata program and USDC mint default to the mainnet addresses, override the public
+    /// fields if the validator uses others.
+    ///
+    /// # Arguments
+    /// * `program_id` - The Darklake program id
+    /// * `lookup_table` - The address lookup table used for swap, settle and cancel transactions
+    /// * `create_pool_fee_vault` - The account receiving the pool creation fee
+    pub fn custom(program_id: Pubkey, lookup_table: Pubkey, create_pool_fee_vault: Pubkey) -> Self {
+        Self {
+            program_id,
+            lookup_table,
+            create_pool_fee_vault,
+            ..Self::mainnet()
+        }
+    }
+
+    /// The program authority PDA that owns the pool reserves
+    pub fn authority(&self) -> Pubkey {
+        Pubkey::find_program_address(&[AUTHORITY_SEED], &self.program_id).0
+    }
+
+    /// The address of the AmmConfig (fee tier) with the given index
+    pub fn amm_config_address(&self, index: u32) -> Pubkey {
+        Pubkey::find_program_address(&[AMM_CONFIG_SEED, &index.to_le_bytes()], &self.program_id).0
+    }
+}
+
+impl Default for DarklakeNetworkConfig {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{AMM_CONFIG, AUTHORITY, DEFAULT_AMM_CONFIG_INDEX};
+
+    #[test]
+    fn test_presets_match_constants() {
+        let mainnet = DarklakeNetworkConfig::mainnet();
+        assert_eq!(mainnet.authority(), *AUTHORITY);
+        assert_eq!(
+            mainnet.amm_config_address(DEFAULT_AMM_CONFIG_INDEX),
+            *AMM_CONFIG
+        );
+        assert_eq!(DarklakeNetworkConfig::from_is_devnet(false), mainnet);
+        assert_eq!(
+            DarklakeNetworkConfig::from_is_devnet(true).lookup_table,
+            DEVNET_LOOKUP
+        );
+    }
+
+    #[test]
+    fn test_custom_program_derives_own_pdas() {
+        let network = DarklakeNetworkConfig::custom(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        assert_ne!(network.authority(), *AUTHORITY);
+        assert_ne!(
+            network.amm_config_address(DEFAULT_AMM_CONFIG_INDEX),
+            *AMM_CONFIG
+        );
+        assert_eq!(network.metadata_program_id, METADATA_PROGRAM_ID);
+    }
+}
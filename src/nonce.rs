@@ -0,0 +1,81 @@
+use anyhow::{Result, bail};
+use solana_sdk::{
+    account::Account,
+    nonce::state::{Data, State, Versions},
+    pubkey::Pubkey,
+    system_program,
+};
+
+/// Durable nonce account used instead of a recent blockhash
+///
+/// Transactions built with a durable nonce start with an `AdvanceNonceAccount` instruction and use
+/// the stored nonce as blockhash, so they stay valid until the nonce is advanced instead of
+/// expiring after ~150 slots. `nonce_authority` has to sign every transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DurableNonceConfig {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub nonce_account: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub nonce_authority: Pubkey,
+}
+
+/// Decode an initialized nonce account
+pub(crate) fn parse_nonce_account(nonce_account: &Pubkey, account: &Account) -> Result<Data> {
+    if account.owner != system_program::ID {
+        bail!(
+            "Nonce account {} is owned by {}, not the system program",
+            nonce_account,
+            account.owner
+        );
+    }
+
+    let versions: Versions = bincode::deserialize(&account.data)?;
+    match versions.state() {
+        State::Initialized(data) => Ok(data.clone()),
+        State::Uninitialized => bail!("Nonce account {} is not initialized", nonce_account),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::nonce_account;
+    use solana_sdk::{hash::Hash, nonce::state::DurableNonce};
+
+    #[test]
+    fn test_parse_nonce_account() {
+        let (nonce_key, authority, blockhash) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Hash::new_unique(),
+        );
+
+        let nonce_account_data = nonce_account(authority, blockhash);
+        let account = Account {
+            lamports: 1_447_680,
+            data: nonce_account_data.data,
+            owner: nonce_account_data.owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        let data = parse_nonce_account(&nonce_key, &account).unwrap();
+        assert_eq!(data.authority, authority);
+        assert_eq!(
+            data.blockhash(),
+            *DurableNonce::from_blockhash(&blockhash).as_hash()
+        );
+
+        let uninitialized = Account {
+            data: bincode::serialize(&Versions::new(State::Uninitialized)).unwrap(),
+            ..account.clone()
+        };
+        assert!(parse_nonce_account(&nonce_key, &uninitialized).is_err());
+
+        let wrong_owner = Account {
+            owner: Pubkey::new_unique(),
+            ..account
+        };
+        assert!(parse_nonce_account(&nonce_key, &wrong_owner).is_err());
+    }
+}
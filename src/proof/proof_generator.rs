@@ -28,10 +28,15 @@ pub struct PublicProofInputs {
 
 /// Represents the generated proof components
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeneratedProof {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub proof_a: [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub proof_b: [u8; 128],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub proof_c: [u8; 64],
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes_vec"))]
     pub public_signals: Vec<[u8; 32]>,
 }
 
@@ -6,19 +6,26 @@ use crate::SwapMode;
 
 /// Swap parameters
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SwapParamsIx {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub source_mint: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub destination_mint: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_transfer_authority: Pubkey,
     pub amount_in: u64,
     pub swap_mode: SwapMode,
     pub min_out: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub salt: [u8; 8],
 }
 
 /// Add liquidity parameters
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddLiquidityParamsIx {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user: Pubkey,
     pub amount_lp: u64, // lp to mint
     pub max_amount_x: u64,
@@ -27,11 +34,17 @@ pub struct AddLiquidityParamsIx {
 
 /// Initialize pool parameters
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InitializePoolParamsIx {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_x: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_x_program: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_y: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub token_y_program: Pubkey,
     pub amount_x: u64,
     pub amount_y: u64,
@@ -39,7 +52,9 @@ pub struct InitializePoolParamsIx {
 
 /// Remove liquidity parameters
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RemoveLiquidityParamsIx {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub user: Pubkey,
     pub amount_lp: u64, // lp to burn
     pub min_amount_x: u64,
@@ -48,14 +63,68 @@ pub struct RemoveLiquidityParamsIx {
 
 /// Finalize parameters
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FinalizeParamsIx {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub settle_signer: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
     pub order_owner: Pubkey,
     pub unwrap_wsol: bool,
     pub min_out: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub salt: [u8; 8],
     pub output: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
     pub commitment: [u8; 32],
     pub deadline: u64,
     pub current_slot: u64,
 }
+
+/// Settle parameters
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SettleParamsIx {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub settle_signer: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub order_owner: Pubkey,
+    pub unwrap_wsol: bool,
+    pub min_out: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
+    pub salt: [u8; 8],
+    pub output: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
+    pub commitment: [u8; 32],
+    pub deadline: u64,
+    pub current_slot: u64,
+}
+
+/// Cancel parameters
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CancelParamsIx {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub settle_signer: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub order_owner: Pubkey,
+    pub min_out: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
+    pub salt: [u8; 8],
+    pub output: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::hex_bytes"))]
+    pub commitment: [u8; 32],
+    pub deadline: u64,
+    pub current_slot: u64,
+}
+
+/// Slash parameters
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlashParamsIx {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub settle_signer: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub order_owner: Pubkey,
+    pub deadline: u64,
+    pub current_slot: u64,
+}
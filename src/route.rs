@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, bail};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{amm::Quote, darklake_amm::DarklakeAmm, darklake_pool::DarklakePool};
+
+/// A swap through a single Darklake pool
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RouteHop {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub pool: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_in: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_out: Pubkey,
+    pub amount_in: u64,
+    pub quote: Quote,
+}
+
+/// A quoted path of one or more hops from `token_in` to `token_out`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Route {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_in: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::pubkey"))]
+    pub token_out: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub hops: Vec<RouteHop>,
+}
+
+/// Get the pool address of a mint pair in an AmmConfig, mints in any order
+pub(crate) fn get_pool_key(
+    program_id: &Pubkey,
+    amm_config: &Pubkey,
+    token_mint_a: &Pubkey,
+    token_mint_b: &Pubkey,
+) -> Pubkey {
+    if token_mint_a < token_mint_b {
+        DarklakeAmm::get_pool_address(program_id, amm_config, token_mint_a, token_mint_b)
+    } else {
+        DarklakeAmm::get_pool_address(program_id, amm_config, token_mint_b, token_mint_a)
+    }
+}
+
+/// Direct path followed by every two-hop path through one of the intermediate mints
+pub(crate) fn get_candidate_paths(
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    intermediate_mints: &[Pubkey],
+) -> Vec<Vec<Pubkey>> {
+    let mut paths = vec![vec![*token_in, *token_out]];
+
+    for intermediate_mint in intermediate_mints {
+        if intermediate_mint == token_in || intermediate_mint == token_out {
+            continue;
+        }
+
+        let path = vec![*token_in, *intermediate_mint, *token_out];
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    paths
+}
+
+/// Pools needed to quote the given paths
+pub(crate) fn get_path_pool_keys(
+    program_id: &Pubkey,
+    amm_config: &Pubkey,
+    paths: &[Vec<Pubkey>],
+) -> Vec<Pubkey> {
+    let mut pool_keys = Vec::new();
+
+    for path in paths {
+        for pair in path.windows(2) {
+            let pool_key = get_pool_key(program_id, amm_config, &pair[0], &pair[1]);
+            if !pool_keys.contains(&pool_key) {
+                pool_keys.push(pool_key);
+            }
+        }
+    }
+
+    pool_keys
+}
+
+/// Quote a path hop by hop, feeding each hop's output into the next one
+pub(crate) fn quote_path(
+    program_id: &Pubkey,
+    amm_config: &Pubkey,
+    path: &[Pubkey],
+    pools: &HashMap<Pubkey, DarklakePool>,
+    amount_in: u64,
+) -> Result<Vec<RouteHop>> {
+    let mut hops = Vec::new();
+    let mut hop_amount_in = amount_in;
+
+    for pair in path.windows(2) {
+        let pool_key = get_pool_key(program_id, amm_config, &pair[0], &pair[1]);
+        let pool = pools
+            .get(&pool_key)
+            .with_context(|| format!("Pool not found for {} and {}", pair[0], pair[1]))?;
+
+        let quote = pool.quote(&pair[0], hop_amount_in)?;
+
+        hops.push(RouteHop {
+            pool: pool_key,
+            token_in: pair[0],
+            token_out: pair[1],
+            amount_in: hop_amount_in,
+            quote: quote.clone(),
+        });
+
+        hop_amount_in = quote.out_amount;
+    }
+
+    Ok(hops)
+}
+
+/// Quote every candidate path and pick the one with the largest output
+pub(crate) fn get_best_route(
+    program_id: &Pubkey,
+    amm_config: &Pubkey,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount_in: u64,
+    paths: &[Vec<Pubkey>],
+    pools: &HashMap<Pubkey, DarklakePool>,
+) -> Result<Route> {
+    let mut best_route: Option<Route> = None;
+
+    for path in paths {
+        let hops = match quote_path(program_id, amm_config, path, pools, amount_in) {
+            Ok(hops) => hops,
+            Err(e) => {
+                log::debug!("Skipping path {:?}: {}", path, e);
+                continue;
+            }
+        };
+
+        let amount_out = hops.last().map(|hop| hop.quote.out_amount).unwrap_or(0);
+
+        if best_route
+            .as_ref()
+            .is_none_or(|best_route| amount_out > best_route.amount_out)
+        {
+            best_route = Some(Route {
+                token_in: *token_in,
+                token_out: *token_out,
+                amount_in,
+                amount_out,
+                hops,
+            });
+        }
+    }
+
+    match best_route {
+        Some(route) => Ok(route),
+        None => bail!("No route found from {} to {}", token_in, token_out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{AMM_CONFIG, DARKLAKE_PROGRAM_ID, DEFAULT_AMM_CONFIG_INDEX};
+    use crate::network::DarklakeNetworkConfig;
+    use crate::test_utils::pool_accounts;
+
+    fn load_pools(pools: &[(Pubkey, Pubkey, u64, u64)]) -> HashMap<Pubkey, DarklakePool> {
+        pools
+            .iter()
+            .map(|(token_mint_a, token_mint_b, reserve_a, reserve_b)| {
+                let (pool_key, _, account_map) =
+                    pool_accounts(*token_mint_a, *token_mint_b, *reserve_a, *reserve_b);
+                let pool = DarklakePool::from_account_map(&pool_key, &account_map, 0).unwrap();
+                (pool_key, pool)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_candidate_paths() {
+        let (token_a, token_b, token_c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        let paths = get_candidate_paths(&token_a, &token_c, &[token_b, token_a, token_b]);
+        assert_eq!(
+            paths,
+            vec![vec![token_a, token_c], vec![token_a, token_b, token_c]]
+        );
+        assert_eq!(
+            get_path_pool_keys(&DARKLAKE_PROGRAM_ID, &AMM_CONFIG, &paths).len(),
+            3
+        );
+        assert_eq!(
+            get_pool_key(&DARKLAKE_PROGRAM_ID, &AMM_CONFIG, &token_a, &token_c),
+            get_pool_key(&DARKLAKE_PROGRAM_ID, &AMM_CONFIG, &token_c, &token_a)
+        );
+        assert_ne!(
+            get_pool_key(&DARKLAKE_PROGRAM_ID, &AMM_CONFIG, &token_a, &token_c),
+            get_pool_key(
+                &DARKLAKE_PROGRAM_ID,
+                &DarklakeNetworkConfig::mainnet().amm_config_address(DEFAULT_AMM_CONFIG_INDEX + 1),
+                &token_a,
+                &token_c
+            )
+        );
+    }
+
+    #[test]
+    fn test_routes_through_intermediate_without_direct_pool() {
+        let (token_a, token_b, token_c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let pools = load_pools(&[
+            (token_a, token_b, 1_000_000_000, 1_000_000_000),
+            (token_b, token_c, 1_000_000_000, 1_000_000_000),
+        ]);
+
+        let paths = get_candidate_paths(&token_a, &token_c, &[token_b]);
+        let route = get_best_route(
+            &DARKLAKE_PROGRAM_ID,
+            &AMM_CONFIG,
+            &token_a,
+            &token_c,
+            1_000_000,
+            &paths,
+            &pools,
+        )
+        .unwrap();
+
+        assert_eq!(route.hops.len(), 2);
+        assert_eq!(route.hops[0].token_out, token_b);
+        assert_eq!(route.hops[1].amount_in, route.hops[0].quote.out_amount);
+        assert_eq!(route.amount_out, route.hops[1].quote.out_amount);
+    }
+
+    #[test]
+    fn test_picks_route_with_best_output() {
+        let (token_a, token_b, token_c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        // the direct pool is shallow, the path through b is deep
+        let pools = load_pools(&[
+            (token_a, token_c, 10_000_000, 10_000_000),
+            (token_a, token_b, 1_000_000_000, 1_000_000_000),
+            (token_b, token_c, 1_000_000_000, 1_000_000_000),
+        ]);
+
+        let paths = get_candidate_paths(&token_a, &token_c, &[token_b]);
+        let route = get_best_route(
+            &DARKLAKE_PROGRAM_ID,
+            &AMM_CONFIG,
+            &token_a,
+            &token_c,
+            1_000_000,
+            &paths,
+            &pools,
+        )
+        .unwrap();
+        assert_eq!(route.hops.len(), 2);
+
+        let small_route = get_best_route(
+            &DARKLAKE_PROGRAM_ID,
+            &AMM_CONFIG,
+            &token_a,
+            &token_c,
+            1_000,
+            &paths,
+            &pools,
+        )
+        .unwrap();
+        assert_eq!(small_route.hops.len(), 1);
+
+        let token_d = Pubkey::new_unique();
+        assert!(
+            get_best_route(
+                &DARKLAKE_PROGRAM_ID,
+                &AMM_CONFIG,
+                &token_c,
+                &token_d,
+                1_000,
+                &get_candidate_paths(&token_c, &token_d, &[token_b]),
+                &pools
+            )
+            .is_err()
+        );
+    }
+}
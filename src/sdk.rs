@@ -3,32 +3,47 @@ use spl_token::native_mint;
 
 use crate::{
     amm::{
-        AccountData, AddLiquidityParams, Amm, CancelParams, FinalizeParams, InitializePoolParams,
-        KeyedAccount, ProofCircuitPaths, ProofParams, Quote, QuoteParams, RemoveLiquidityParams,
-        SettleParams, SlashParams, SwapMode, SwapParams,
+        AccountData, AccountMap, AddLiquidityParams, Amm, AmmConfigInfo, CancelParams,
+        InitializePoolParams, KeyedAccount, LiquidityQuote, LpPosition, PoolInfo, PoolStatus,
+        PreflightIssue, ProofCircuitPaths, ProofParams, Quote, QuoteParams, RemoveLiquidityParams,
+        SettleParams, SlashParams, SwapMode, SwapParams, ZapQuote,
     },
-    constants::{DARKLAKE_PROGRAM_ID, SOL_MINT},
+    builder::{DarklakeSDKBuilder, TxDefaults},
+    chain_reader::{ChainReader, RpcChainReader},
+    constants::{AMM_CONFIG_DISCRIMINATOR, POOL_DISCRIMINATOR, SOL_MINT},
     darklake_amm::{AmmConfig, DarklakeAmm, Order, Pool},
+    darklake_pool::DarklakePool,
+    error::SwapPreflightError,
+    lookup_table_cache::LookupTableCache,
+    network::DarklakeNetworkConfig,
+    nonce::{DurableNonceConfig, parse_nonce_account},
     proof::proof_generator::find_circuit_path,
     reduced_amm_params::{
-        AddLiquidityParamsIx, FinalizeParamsIx, InitializePoolParamsIx, RemoveLiquidityParamsIx,
-        SwapParamsIx,
+        AddLiquidityParamsIx, CancelParamsIx, FinalizeParamsIx, InitializePoolParamsIx,
+        RemoveLiquidityParamsIx, SettleParamsIx, SlashParamsIx, SwapParamsIx,
     },
+    route::{Route, get_best_route, get_candidate_paths, get_path_pool_keys, get_pool_key},
+    token_extensions::{MintExtensions, add_transfer_hook_accounts, get_token_account_len},
+    tx_plan::DarklakeTxPlan,
     utils::{
-        convert_string_to_bytes_array, generate_random_salt, get_address_lookup_table,
-        get_close_wsol_instructions, get_wrap_sol_to_wsol_instructions,
+        compile_v0_transaction, convert_string_to_bytes_array, generate_random_salt,
+        get_close_wsol_instructions, get_min_out_with_slippage, get_preflight_issues,
+        get_transfer_fee, get_wrap_sol_to_wsol_instructions,
     },
 };
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount,
     commitment_config::{CommitmentConfig, CommitmentLevel},
     compute_budget::ComputeBudgetInstruction,
     instruction::Instruction,
-    message::{VersionedMessage, v0},
+    nonce::state::{Data as NonceData, State as NonceState},
     pubkey::Pubkey,
-    transaction::VersionedTransaction,
+    signature::{Keypair, Signature, Signer},
+    system_instruction,
+    transaction::{TransactionError, VersionedTransaction},
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 use tokio::time::{Duration, sleep};
 
 use crate::proof::proof_generator::{
@@ -37,13 +52,20 @@ use crate::proof::proof_generator::{
 };
 
 pub struct DarklakeSDK {
-    rpc_client: RpcClient,
+    chain: Arc<dyn ChainReader>,
+    rpc_client: Option<Arc<RpcClient>>,
     darklake_amm: DarklakeAmm,
     settle_paths: ProofCircuitPaths,
     cancel_paths: ProofCircuitPaths,
-    is_devnet: bool, // supports only devnet or mainnet
+    network: DarklakeNetworkConfig,
     label: Option<[u8; 21]>,
     ref_code: Option<[u8; 20]>,
+    slash_expired_orders: bool,
+    amm_config: Pubkey,
+    tx_defaults: TxDefaults,
+    lookup_table_cache: LookupTableCache,
+    extra_lookup_tables: Vec<Pubkey>,
+    durable_nonce: Option<DurableNonceConfig>,
 }
 
 impl DarklakeSDK {
@@ -51,12 +73,82 @@ impl DarklakeSDK {
     pub fn new(
         rpc_endpoint: &str,
         commitment_level: CommitmentLevel,
-        is_devnet: bool, // only used for pool initialization
+        is_devnet: bool,
         label: Option<&str>,
         ref_code: Option<&str>,
     ) -> Result<Self> {
-        let commitment_config = CommitmentConfig {
-            commitment: commitment_level,
+        Self::new_with_network(
+            rpc_endpoint,
+            commitment_level,
+            DarklakeNetworkConfig::from_is_devnet(is_devnet),
+            label,
+            ref_code,
+        )
+    }
+
+    /// Create a new Darklake SDK instance for the given deployment, e.g. a fork of the program
+    /// on a local validator
+    pub fn new_with_network(
+        rpc_endpoint: &str,
+        commitment_level: CommitmentLevel,
+        network: DarklakeNetworkConfig,
+        label: Option<&str>,
+        ref_code: Option<&str>,
+    ) -> Result<Self> {
+        let mut builder = DarklakeSDKBuilder::new()
+            .rpc_endpoint(rpc_endpoint)
+            .commitment(commitment_level)
+            .network(network);
+        if let Some(label) = label {
+            builder = builder.label(label);
+        }
+        if let Some(ref_code) = ref_code {
+            builder = builder.ref_code(ref_code);
+        }
+
+        builder.build()
+    }
+
+    /// Start building an SDK with a shared RPC client, custom circuits or transaction defaults
+    pub fn builder() -> DarklakeSDKBuilder {
+        DarklakeSDKBuilder::new()
+    }
+
+    pub(crate) fn from_builder(builder: DarklakeSDKBuilder) -> Result<Self> {
+        let DarklakeSDKBuilder {
+            rpc_client,
+            rpc_endpoint,
+            rpc_sender: _,
+            chain_reader,
+            commitment_level,
+            network,
+            label,
+            ref_code,
+            circuits_dir,
+            amm_config_index,
+            slash_expired_orders,
+            tx_defaults,
+            lookup_table_ttl,
+            extra_lookup_tables,
+            durable_nonce,
+        } = builder;
+        let label = label.as_deref();
+        let ref_code = ref_code.as_deref();
+
+        let rpc_client = match (rpc_client, rpc_endpoint) {
+            (Some(rpc_client), _) => Some(rpc_client),
+            (None, Some(rpc_endpoint)) => Some(Arc::new(RpcClient::new_with_commitment(
+                rpc_endpoint,
+                CommitmentConfig {
+                    commitment: commitment_level,
+                },
+            ))),
+            (None, None) => None,
+        };
+        let chain: Arc<dyn ChainReader> = match (chain_reader, &rpc_client) {
+            (Some(chain_reader), _) => chain_reader,
+            (None, Some(rpc_client)) => Arc::new(RpcChainReader::new(rpc_client.clone())),
+            (None, None) => bail!("An RPC endpoint, client, sender or chain reader is required"),
         };
 
         // label
@@ -102,16 +194,22 @@ impl DarklakeSDK {
         let settle_file_prefix = "settle";
         let cancel_file_prefix = "cancel";
 
-        let settle_wasm_path = find_circuit_path(&format!("{}.wasm", settle_file_prefix));
-        let settle_zkey_path = find_circuit_path(&format!("{}_final.zkey", settle_file_prefix));
-        let settle_r1cs_path = find_circuit_path(&format!("{}.r1cs", settle_file_prefix));
+        let circuit_path = |filename: String| match &circuits_dir {
+            Some(circuits_dir) => format!("{}/{}", circuits_dir.trim_end_matches('/'), filename),
+            None => find_circuit_path(&filename),
+        };
+
+        let settle_wasm_path = circuit_path(format!("{}.wasm", settle_file_prefix));
+        let settle_zkey_path = circuit_path(format!("{}_final.zkey", settle_file_prefix));
+        let settle_r1cs_path = circuit_path(format!("{}.r1cs", settle_file_prefix));
 
-        let cancel_wasm_path = find_circuit_path(&format!("{}.wasm", cancel_file_prefix));
-        let cancel_zkey_path = find_circuit_path(&format!("{}_final.zkey", cancel_file_prefix));
-        let cancel_r1cs_path = find_circuit_path(&format!("{}.r1cs", cancel_file_prefix));
+        let cancel_wasm_path = circuit_path(format!("{}.wasm", cancel_file_prefix));
+        let cancel_zkey_path = circuit_path(format!("{}_final.zkey", cancel_file_prefix));
+        let cancel_r1cs_path = circuit_path(format!("{}.r1cs", cancel_file_prefix));
 
         Ok(Self {
-            rpc_client: RpcClient::new_with_commitment(rpc_endpoint.to_string(), commitment_config),
+            chain,
+            rpc_client,
             darklake_amm: DarklakeAmm {
                 key: Pubkey::default(),
                 pool: Pool::default(),
@@ -122,6 +220,9 @@ impl DarklakeSDK {
                 token_y_owner: Pubkey::default(),
                 token_x_transfer_fee_config: None,
                 token_y_transfer_fee_config: None,
+                token_x_extensions: MintExtensions::default(),
+                token_y_extensions: MintExtensions::default(),
+                network: network.clone(),
             },
             settle_paths: ProofCircuitPaths {
                 wasm_path: settle_wasm_path,
@@ -133,12 +234,171 @@ impl DarklakeSDK {
                 zkey_path: cancel_zkey_path,
                 r1cs_path: cancel_r1cs_path,
             },
-            is_devnet,
+            amm_config: network.amm_config_address(amm_config_index),
+            network,
             label: Some(full_label_bytes),
             ref_code: ref_code_bytes,
+            slash_expired_orders,
+            tx_defaults,
+            lookup_table_cache: LookupTableCache::new(lookup_table_ttl),
+            extra_lookup_tables,
+            durable_nonce,
         })
     }
 
+    /// The Darklake deployment this SDK builds instructions for
+    pub fn network(&self) -> &DarklakeNetworkConfig {
+        &self.network
+    }
+
+    /// Use the AmmConfig (fee tier) with the given index for pool derivation and initialization
+    ///
+    /// Pools are derived from their AmmConfig, so a mint pair has one pool per fee tier. Index
+    /// `DEFAULT_AMM_CONFIG_INDEX` (0) is used unless another one is set.
+    pub fn set_amm_config_index(&mut self, index: u32) {
+        self.amm_config = self.network.amm_config_address(index);
+    }
+
+    /// Use the AmmConfig (fee tier) at the given address, e.g. one returned by `list_amm_configs`
+    pub fn set_amm_config(&mut self, amm_config: Pubkey) {
+        self.amm_config = amm_config;
+    }
+
+    /// The address of the AmmConfig pools are derived from
+    pub fn amm_config(&self) -> Pubkey {
+        self.amm_config
+    }
+
+    /// Get the address of the AmmConfig (fee tier) with the given index
+    pub fn get_amm_config_address(&self, index: u32) -> Pubkey {
+        self.network.amm_config_address(index)
+    }
+
+    /// Slash the trader's expired order in the same transaction instead of failing in `swap_tx`
+    ///
+    /// Only one order per trader and pool can be open. An order that is still open past its
+    /// deadline can only be slashed, which returns the locked input to the trader.
+    pub fn set_slash_expired_orders(&mut self, slash_expired_orders: bool) {
+        self.slash_expired_orders = slash_expired_orders;
+    }
+
+    /// Compile the given lookup tables into every transaction next to the network's
+    pub fn set_extra_lookup_tables(&mut self, extra_lookup_tables: Vec<Pubkey>) {
+        self.extra_lookup_tables = extra_lookup_tables;
+    }
+
+    /// Get the network's lookup table followed by the extra lookup tables
+    ///
+    /// Tables are served from the cache until they are older than the builder's
+    /// `lookup_table_ttl`.
+    ///
+    /// # Returns
+    /// Returns the `AddressLookupTableAccount`s the `_tx` functions compile transactions with
+    pub async fn get_lookup_tables(&self) -> Result<Vec<AddressLookupTableAccount>> {
+        let mut lookup_table_keys = vec![self.network.lookup_table];
+        for lookup_table_key in &self.extra_lookup_tables {
+            if !lookup_table_keys.contains(lookup_table_key) {
+                lookup_table_keys.push(*lookup_table_key);
+            }
+        }
+
+        self.lookup_table_cache
+            .get(self.chain.as_ref(), &lookup_table_keys)
+            .await
+    }
+
+    /// Drop the cached lookup tables, e.g. after extending one of the extra lookup tables
+    pub fn invalidate_lookup_tables(&self) {
+        self.lookup_table_cache.invalidate();
+    }
+
+    /// Build every `_tx` transaction with the durable nonce instead of a recent blockhash, or
+    /// go back to recent blockhashes with `None`
+    ///
+    /// The nonce authority has to sign each transaction next to its other signers. Only one
+    /// transaction can be sent per nonce value, build the next one after the previous landed.
+    pub fn set_durable_nonce(&mut self, durable_nonce: Option<DurableNonceConfig>) {
+        self.durable_nonce = durable_nonce;
+    }
+
+    /// The durable nonce the `_tx` functions use, if any
+    pub fn durable_nonce(&self) -> Option<&DurableNonceConfig> {
+        self.durable_nonce.as_ref()
+    }
+
+    /// Get the state of a nonce account
+    ///
+    /// # Arguments
+    /// * `nonce_account` - The nonce account address
+    ///
+    /// # Returns
+    /// Returns the nonce authority, the nonce value used as blockhash (`blockhash()`) and the fee
+    /// per signature, or an error if the account does not exist or is not an initialized nonce
+    /// account
+    pub async fn get_nonce_data(&self, nonce_account: &Pubkey) -> Result<NonceData> {
+        let account = self
+            .chain
+            .get_account(nonce_account)
+            .await?
+            .with_context(|| format!("Nonce account {} not found", nonce_account))?;
+
+        parse_nonce_account(nonce_account, &account)
+    }
+
+    /// Create the instructions funding and initializing a nonce account with the rent-exempt
+    /// minimum
+    ///
+    /// # Arguments
+    /// * `payer` - The account paying the rent
+    /// * `nonce_account` - The new nonce account, it has to sign
+    /// * `nonce_authority` - The account allowed to advance the nonce
+    ///
+    /// # Returns
+    /// Returns the system program's create account and initialize nonce instructions
+    pub async fn create_nonce_account_ix(
+        &self,
+        payer: &Pubkey,
+        nonce_account: &Pubkey,
+        nonce_authority: &Pubkey,
+    ) -> Result<Vec<Instruction>> {
+        let lamports = self
+            .chain
+            .get_minimum_balance_for_rent_exemption(NonceState::size())
+            .await?;
+
+        Ok(system_instruction::create_nonce_account(
+            payer,
+            nonce_account,
+            nonce_authority,
+            lamports,
+        ))
+    }
+
+    /// Create a nonce account
+    ///
+    /// Always uses a recent blockhash, even if a durable nonce is set.
+    ///
+    /// # Arguments
+    /// * `payer` - The account paying the fees and rent
+    /// * `nonce_account` - The new nonce account, it has to sign
+    /// * `nonce_authority` - The account allowed to advance the nonce
+    ///
+    /// # Returns
+    /// Returns a `VersionedTransaction` to be signed by the payer and the nonce account
+    pub async fn create_nonce_account_tx(
+        &self,
+        payer: &Pubkey,
+        nonce_account: &Pubkey,
+        nonce_authority: &Pubkey,
+    ) -> Result<VersionedTransaction> {
+        let instructions = self
+            .create_nonce_account_ix(payer, nonce_account, nonce_authority)
+            .await?;
+        let recent_blockhash = self.chain.get_latest_blockhash().await?;
+
+        compile_v0_transaction(payer, &instructions, &[], recent_blockhash)
+    }
+
     /// Get a quote for a swap
     ///
     /// # Arguments
@@ -168,7 +428,7 @@ impl DarklakeSDK {
             token_out.clone()
         };
 
-        let (pool_key, _token_x, _token_y) = Self::get_pool_address(&_token_in, &_token_out);
+        let (pool_key, _token_x, _token_y) = self.get_pool_address(&_token_in, &_token_out);
 
         if self.darklake_amm.key() != pool_key {
             self.load_pool(&_token_x, &_token_y).await?;
@@ -176,7 +436,7 @@ impl DarklakeSDK {
 
         self.update_accounts().await?;
 
-        let epoch = self.rpc_client.get_epoch_info().await?.epoch;
+        let epoch = self.chain.get_epoch().await?;
 
         self.darklake_amm.quote(&QuoteParams {
             input_mint: _token_in,
@@ -186,40 +446,42 @@ impl DarklakeSDK {
         })
     }
 
-    /// Start a swap
+    /// Quote the deposit needed to add liquidity to a pool
     ///
     /// # Arguments
-    /// * `token_in` - The input token mint
-    /// * `token_out` - The output token mint
-    /// * `amount_in` - The amount of input tokens
-    /// * `min_out` - The minimum amount of output tokens expected
-    /// * `token_owner` - The token owner public key
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
+    /// * `token` - The token the amount is given in, either `token_x` or `token_y`
+    /// * `amount` - The amount of `token` to deposit
     ///
     /// # Returns
-    /// Returns a `VersionedTransaction`, the order key, the minimum amount of output tokens expected (min_out), and the salt used
-    pub async fn swap_tx(
+    /// Returns a `LiquidityQuote` with the LP tokens minted and the required amounts of both
+    /// tokens, ordered by the pool's sorted mints
+    pub async fn quote_add_liquidity(
         &mut self,
-        token_in: &Pubkey,
-        token_out: &Pubkey,
-        amount_in: u64,
-        min_out: u64,
-        token_owner: &Pubkey,
-    ) -> Result<(VersionedTransaction, Pubkey, u64, [u8; 8])> {
-        let is_from_sol = *token_in == SOL_MINT;
-        let is_to_sol = *token_out == SOL_MINT;
-
-        let _token_in = if is_from_sol {
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+        token: &Pubkey,
+        amount: u64,
+    ) -> Result<LiquidityQuote> {
+        let token_x_post_sol = if *token_x == SOL_MINT {
             native_mint::ID
         } else {
-            token_in.clone()
+            *token_x
         };
-        let _token_out = if is_to_sol {
+        let token_y_post_sol = if *token_y == SOL_MINT {
             native_mint::ID
         } else {
-            token_out.clone()
+            *token_y
+        };
+        let token_post_sol = if *token == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token
         };
 
-        let (pool_key, _token_x, _token_y) = Self::get_pool_address(&_token_in, &_token_out);
+        let (pool_key, _token_x, _token_y) =
+            self.get_pool_address(&token_x_post_sol, &token_y_post_sol);
 
         if self.darklake_amm.key() != pool_key {
             self.load_pool(&_token_x, &_token_y).await?;
@@ -227,202 +489,152 @@ impl DarklakeSDK {
 
         self.update_accounts().await?;
 
-        let salt = generate_random_salt();
+        self.darklake_amm
+            .quote_add_liquidity(&token_post_sol, amount)
+    }
 
-        let swap_params = SwapParamsIx {
-            source_mint: _token_in,
-            destination_mint: _token_out,
-            token_transfer_authority: token_owner.clone(),
-            amount_in,
-            swap_mode: SwapMode::ExactIn,
-            min_out,
-            salt,
+    /// Quote the tokens returned for removing liquidity from a pool
+    ///
+    /// # Arguments
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
+    /// * `amount_lp` - Amount of LP tokens to burn
+    ///
+    /// # Returns
+    /// Returns a `LiquidityQuote` with the amounts of both tokens received, ordered by the pool's
+    /// sorted mints
+    pub async fn quote_remove_liquidity(
+        &mut self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+        amount_lp: u64,
+    ) -> Result<LiquidityQuote> {
+        let token_x_post_sol = if *token_x == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_x
+        };
+        let token_y_post_sol = if *token_y == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_y
         };
 
-        let swap_instruction = self.swap_ix(&swap_params).await?;
-
-        let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(300_000);
-
-        let mut instructions = vec![compute_budget_ix];
-
-        let address_lookup_table_account =
-            get_address_lookup_table(&self.rpc_client, self.is_devnet).await?;
+        let (pool_key, _token_x, _token_y) =
+            self.get_pool_address(&token_x_post_sol, &token_y_post_sol);
 
-        if is_from_sol {
-            let sol_to_wsol_instructions =
-                get_wrap_sol_to_wsol_instructions(&token_owner, amount_in)?;
-            instructions.push(sol_to_wsol_instructions[0].clone());
-            instructions.push(sol_to_wsol_instructions[1].clone());
-            instructions.push(sol_to_wsol_instructions[2].clone());
+        if self.darklake_amm.key() != pool_key {
+            self.load_pool(&_token_x, &_token_y).await?;
         }
 
-        instructions.push(swap_instruction);
-
-        let recent_blockhash = self.rpc_client.get_latest_blockhash().await?;
-        let message_v0 = v0::Message::try_compile(
-            &token_owner,
-            &instructions,
-            &[address_lookup_table_account],
-            recent_blockhash,
-        )?;
-
-        let swap_transaction = VersionedTransaction {
-            signatures: vec![],
-            message: VersionedMessage::V0(message_v0),
-        };
-
-        let order_key = self.darklake_amm.get_order_pubkey(&token_owner)?;
+        self.update_accounts().await?;
 
-        Ok((swap_transaction, order_key, min_out, salt))
+        self.darklake_amm.quote_remove_liquidity(amount_lp)
     }
 
-    /// Finalize a swap order by settling, canceling, or slashing it
+    /// Get the Token-2022 extensions of a pool's mints
+    ///
+    /// Loading a pool fails for unsupported extensions (non-transferable mints), the returned
+    /// extensions can be used to surface the warnings of supported ones.
     ///
     /// # Arguments
-    /// * `order_key` - The public key of the order to finalize
-    /// * `unwrap_wsol` - Whether to unwrap WSOL to SOL after settlement
-    /// * `min_out` - The minimum output amount expected (same as swap)
-    /// * `salt` - The salt used in the original swap (same as swap)
-    /// * `settle_signer` - Optional signer for settlement (defaults to order owner)
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
     ///
     /// # Returns
-    /// Returns a `VersionedTransaction` ready to be signed and sent
-    pub async fn finalize_tx(
+    /// Returns the `MintExtensions` of both mints, ordered by the pool's sorted mints
+    pub async fn get_mint_extensions(
         &mut self,
-        order_key: &Pubkey,
-        unwrap_wsol: bool,
-        min_out: u64,
-        salt: [u8; 8],
-        settle_signer: Option<&Pubkey>,
-    ) -> Result<VersionedTransaction> {
-        // Retry getting order data 5 times every 5 seconds
-        let mut order_data = None;
-        for attempt in 1..=5 {
-            match self.rpc_client.get_account(&order_key).await {
-                Ok(account) => {
-                    order_data = Some(account);
-                    break;
-                }
-                Err(e) => {
-                    if attempt == 5 {
-                        return Err(e).context("Failed to get order data after 5 attempts");
-                    }
-                    log::warn!(
-                        "Attempt {} failed to get order data: {}. Retrying in 5 seconds...",
-                        attempt,
-                        e
-                    );
-                    sleep(Duration::from_secs(5)).await;
-                }
-            }
-        }
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+    ) -> Result<(MintExtensions, MintExtensions)> {
+        let token_x_post_sol = if *token_x == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_x
+        };
+        let token_y_post_sol = if *token_y == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_y
+        };
 
-        // Verify we got the order data
-        if order_data.is_none() {
-            return Err(anyhow::anyhow!(
-                "Failed to get order data after all retry attempts"
-            ));
-        }
+        let (pool_key, _token_x, _token_y) =
+            self.get_pool_address(&token_x_post_sol, &token_y_post_sol);
 
-        let order = self
-            .darklake_amm
-            .parse_order_data(&order_data.unwrap().data)?;
+        if self.darklake_amm.key() != pool_key {
+            self.load_pool(&_token_x, &_token_y).await?;
+        }
 
         self.update_accounts().await?;
 
-        let settler = settle_signer.unwrap_or(&order.trader);
-        let create_wsol_ata_ix =
-            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
-                &settler,
-                &settler,
-                &native_mint::ID,
-                &spl_token::ID,
-            );
+        Ok((
+            self.darklake_amm.token_x_extensions.clone(),
+            self.darklake_amm.token_y_extensions.clone(),
+        ))
+    }
 
-        let finalize_params = FinalizeParamsIx {
-            settle_signer: settler.clone(), // who settles the order
-            order_owner: order.trader,      // who owns the order
-            unwrap_wsol,                    // Set to true if you want to unwrap WSOL to SOL
-            min_out,                        // Same min_out as swap
-            salt,                           // Same salt as swap
-            output: order.d_out,            // order prop
-            commitment: order.c_min,        // order prop
-            deadline: order.deadline,       // order prop
-            current_slot: self
-                .rpc_client
-                .get_slot_with_commitment(CommitmentConfig::processed())
-                .await?,
+    /// Get whether a pool is halted and its reserve balances
+    ///
+    /// # Arguments
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
+    ///
+    /// # Returns
+    /// Returns the `PoolStatus` with the halted flag, reserve balances and available reserves
+    pub async fn pool_status(&mut self, token_x: &Pubkey, token_y: &Pubkey) -> Result<PoolStatus> {
+        let token_x_post_sol = if *token_x == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_x
+        };
+        let token_y_post_sol = if *token_y == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_y
         };
 
-        let finalize_instruction = self.finalize_ix(&finalize_params).await?;
-
-        let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(500_000);
-
-        let instructions = vec![compute_budget_ix, create_wsol_ata_ix, finalize_instruction];
-
-        let address_lookup_table_account =
-            get_address_lookup_table(&self.rpc_client, self.is_devnet).await?;
+        let (pool_key, _token_x, _token_y) =
+            self.get_pool_address(&token_x_post_sol, &token_y_post_sol);
 
-        let recent_blockhash = self.rpc_client.get_latest_blockhash().await?;
-        let message_v0 = v0::Message::try_compile(
-            &settler,
-            &instructions,
-            &[address_lookup_table_account],
-            recent_blockhash,
-        )?;
+        if self.darklake_amm.key() != pool_key {
+            self.load_pool(&_token_x, &_token_y).await?;
+        }
 
-        let finalize_transaction = VersionedTransaction {
-            signatures: vec![],
-            message: VersionedMessage::V0(message_v0),
-        };
+        self.update_accounts().await?;
 
-        Ok(finalize_transaction)
+        Ok(self.darklake_amm.get_pool_status())
     }
 
-    /// Add liquidity to a pool
+    /// Get a user's LP position in a pool
     ///
     /// # Arguments
+    /// * `user` - The user's public key
     /// * `token_x` - The first token mint address
     /// * `token_y` - The second token mint address
-    /// * `max_amount_x` - Maximum amount of token_x to add
-    /// * `max_amount_y` - Maximum amount of token_y to add
-    /// * `amount_lp` - Amount of LP tokens to mint
-    /// * `user` - The user's public key
     ///
     /// # Returns
-    /// Returns a `VersionedTransaction` ready to be signed and sent
-    pub async fn add_liquidity_tx(
+    /// Returns an `LpPosition` with the user's LP balance, pool share, underlying token amounts and
+    /// share of the accumulated LP fees
+    pub async fn get_lp_position(
         &mut self,
+        user: &Pubkey,
         token_x: &Pubkey,
         token_y: &Pubkey,
-        max_amount_x: u64,
-        max_amount_y: u64,
-        amount_lp: u64,
-        user: &Pubkey,
-    ) -> Result<VersionedTransaction> {
-        let is_x_sol = *token_x == SOL_MINT;
-        let is_y_sol = *token_y == SOL_MINT;
-
-        let token_x_post_sol = if is_x_sol {
+    ) -> Result<LpPosition> {
+        let token_x_post_sol = if *token_x == SOL_MINT {
             native_mint::ID
         } else {
-            token_x.clone()
+            *token_x
         };
-        let token_y_post_sol = if is_y_sol {
+        let token_y_post_sol = if *token_y == SOL_MINT {
             native_mint::ID
         } else {
-            token_y.clone()
+            *token_y
         };
 
         let (pool_key, _token_x, _token_y) =
-            Self::get_pool_address(&token_x_post_sol, &token_y_post_sol);
-
-        // swaps ammount of token_x and token_y if tokens are not sorted
-        let (max_amount_x, max_amount_y) = if _token_x != token_x_post_sol {
-            (max_amount_y, max_amount_x)
-        } else {
-            (max_amount_x, max_amount_y)
-        };
+            self.get_pool_address(&token_x_post_sol, &token_y_post_sol);
 
         if self.darklake_amm.key() != pool_key {
             self.load_pool(&_token_x, &_token_y).await?;
@@ -430,92 +642,58 @@ impl DarklakeSDK {
 
         self.update_accounts().await?;
 
-        let add_liquidity_params = AddLiquidityParamsIx {
-            amount_lp,
-            max_amount_x,
-            max_amount_y,
-            user: user.clone(),
-        };
-
-        let add_liquidity_instruction = self.add_liquidity_ix(&add_liquidity_params).await?;
-
-        let mut instructions = vec![];
-        if is_x_sol {
-            let sol_to_wsol_instructions = get_wrap_sol_to_wsol_instructions(&user, max_amount_x)?;
-            instructions.push(sol_to_wsol_instructions[0].clone());
-            instructions.push(sol_to_wsol_instructions[1].clone());
-            instructions.push(sol_to_wsol_instructions[2].clone());
-        } else if is_y_sol {
-            let sol_to_wsol_instructions = get_wrap_sol_to_wsol_instructions(&user, max_amount_y)?;
-            instructions.push(sol_to_wsol_instructions[0].clone());
-            instructions.push(sol_to_wsol_instructions[1].clone());
-            instructions.push(sol_to_wsol_instructions[2].clone());
-        }
-
-        instructions.push(add_liquidity_instruction);
-
-        let address_lookup_table_account =
-            get_address_lookup_table(&self.rpc_client, self.is_devnet).await?;
-
-        let recent_blockhash = self.rpc_client.get_latest_blockhash().await?;
-        let message_v0 = v0::Message::try_compile(
-            &user,
-            &instructions,
-            &[address_lookup_table_account],
-            recent_blockhash,
-        )?;
-
-        let add_liquidity_transaction = VersionedTransaction {
-            signatures: vec![],
-            message: VersionedMessage::V0(message_v0),
+        let token_mint_lp = DarklakeAmm::get_token_mint_lp(&self.network.program_id, pool_key);
+        let user_token_account_lp =
+            DarklakeAmm::get_user_token_account(*user, token_mint_lp, spl_token::ID);
+
+        let amount_lp = match self.chain.get_account(&user_token_account_lp).await? {
+            Some(account) => DarklakeAmm::parse_token_account_balance(
+                &account.data,
+                &account.owner,
+                &user_token_account_lp,
+            )?,
+            None => 0,
         };
 
-        Ok(add_liquidity_transaction)
+        self.darklake_amm.get_lp_position(amount_lp)
     }
 
-    /// Remove liquidity from a pool
+    /// Check that a user can fund a swap before building it
+    ///
+    /// Fetches the source token account and the user's SOL balance. SOL inputs are wrapped in the
+    /// swap transaction so they only need lamports. Every swap also needs lamports for the
+    /// AmmConfig's `wsol_trade_deposit` and the rent of the order account.
     ///
     /// # Arguments
-    /// * `token_x` - The first token mint address
-    /// * `token_y` - The second token mint address
-    /// * `min_amount_x` - Minimum amount of token_x to receive
-    /// * `min_amount_y` - Minimum amount of token_y to receive
-    /// * `amount_lp` - Amount of LP tokens to burn
+    /// * `token_in` - The input token mint
+    /// * `token_out` - The output token mint
+    /// * `amount_in` - The amount of input tokens
     /// * `user` - The user's public key
     ///
     /// # Returns
-    /// Returns a `VersionedTransaction` ready to be signed and sent
-    pub async fn remove_liquidity_tx(
+    /// Returns every `PreflightIssue` found, empty if the swap can be funded
+    pub async fn preflight_swap(
         &mut self,
-        token_x: &Pubkey,
-        token_y: &Pubkey,
-        min_amount_x: u64,
-        min_amount_y: u64,
-        amount_lp: u64,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
         user: &Pubkey,
-    ) -> Result<VersionedTransaction> {
-        let is_x_sol = *token_x == SOL_MINT;
-        let is_y_sol = *token_y == SOL_MINT;
+    ) -> Result<Vec<PreflightIssue>> {
+        let is_from_sol = *token_in == SOL_MINT;
 
-        let token_x_post_sol = if is_x_sol {
+        let token_in_post_sol = if is_from_sol {
             native_mint::ID
         } else {
-            token_x.clone()
+            *token_in
         };
-        let token_y_post_sol = if is_y_sol {
+        let token_out_post_sol = if *token_out == SOL_MINT {
             native_mint::ID
         } else {
-            token_y.clone()
+            *token_out
         };
 
         let (pool_key, _token_x, _token_y) =
-            Self::get_pool_address(&token_x_post_sol, &token_y_post_sol);
-
-        let (min_amount_x, min_amount_y) = if _token_x != token_x_post_sol {
-            (min_amount_y, min_amount_x)
-        } else {
-            (min_amount_x, min_amount_y)
-        };
+            self.get_pool_address(&token_in_post_sol, &token_out_post_sol);
 
         if self.darklake_amm.key() != pool_key {
             self.load_pool(&_token_x, &_token_y).await?;
@@ -523,353 +701,2232 @@ impl DarklakeSDK {
 
         self.update_accounts().await?;
 
-        let (token_x_owner, token_y_owner) = self.darklake_amm.get_token_owners();
-
-        // make sure the user has the token accounts
-        let create_token_x_ata_ix =
-            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
-                user,
-                user,
-                &_token_x,
-                &token_x_owner,
-            );
-
-        let create_token_y_ata_ix =
-            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
-                user,
-                user,
-                &_token_y,
-                &token_y_owner,
+        let mut token_balances = vec![];
+        let mut required_lamports = self
+            .darklake_amm
+            .amm_config
+            .wsol_trade_deposit
+            .saturating_add(
+                self.chain
+                    .get_minimum_balance_for_rent_exemption(8 + Order::LEN)
+                    .await?,
             );
 
-        let remove_liquidity_params = RemoveLiquidityParamsIx {
-            amount_lp,
-            min_amount_x,
-            min_amount_y,
-            user: user.clone(),
-        };
-
-        let remove_liquidity_instruction =
-            self.remove_liquidity_ix(&remove_liquidity_params).await?;
-
-        let mut instructions = vec![
-            create_token_x_ata_ix,
-            create_token_y_ata_ix,
-            remove_liquidity_instruction,
-        ];
-
-        // Add close WSOL instructions if either token is SOL (user can't have multiple WSOL accounts)
-        if is_x_sol || is_y_sol {
-            let close_wsol_instructions = get_close_wsol_instructions(user)?;
-            instructions.push(close_wsol_instructions[0].clone());
-            instructions.push(close_wsol_instructions[1].clone());
+        let (token_account_in, balance_in) = self
+            .get_user_token_balance(user, &token_in_post_sol)
+            .await?;
+        if is_from_sol {
+            required_lamports = required_lamports.saturating_add(amount_in);
+            if balance_in.is_none() {
+                let rent = self.get_token_account_rent(&token_in_post_sol).await?;
+                required_lamports = required_lamports.saturating_add(rent);
+            }
+        } else {
+            token_balances.push((token_in_post_sol, token_account_in, balance_in, amount_in));
         }
 
-        let address_lookup_table_account =
-            get_address_lookup_table(&self.rpc_client, self.is_devnet).await?;
-
-        let recent_blockhash = self.rpc_client.get_latest_blockhash().await?;
-        let message_v0 = v0::Message::try_compile(
-            &user,
-            &instructions,
-            &[address_lookup_table_account],
-            recent_blockhash,
-        )?;
-
-        let remove_liquidity_transaction = VersionedTransaction {
-            signatures: vec![],
-            message: VersionedMessage::V0(message_v0),
-        };
+        let sol_balance = self.get_sol_balance(user).await?;
 
-        Ok(remove_liquidity_transaction)
+        Ok(get_preflight_issues(
+            &token_balances,
+            sol_balance,
+            required_lamports,
+        ))
     }
 
-    /// Initialize a new liquidity pool
+    /// Start a swap
+    ///
+    /// Fails with a `SwapPreflightError` if the pool is halted or `token_owner` already has an open
+    /// order in the pool. An expired order is slashed in the same transaction instead when
+    /// `set_slash_expired_orders(true)` was called.
     ///
     /// # Arguments
-    /// * `token_x` - The first token mint address
-    /// * `token_y` - The second token mint address
-    /// * `amount_x` - Initial amount of token_x to add
-    /// * `amount_y` - Initial amount of token_y to add
-    /// * `user` - The user's public key
+    /// * `token_in` - The input token mint
+    /// * `token_out` - The output token mint
+    /// * `amount_in` - The amount of input tokens
+    /// * `min_out` - The minimum amount of output tokens expected
+    /// * `token_owner` - The token owner public key
     ///
     /// # Returns
-    /// Returns a `VersionedTransaction` ready to be signed and sent
-    pub async fn initialize_pool_tx(
+    /// Returns a `VersionedTransaction`, the order key, the minimum amount of output tokens expected (min_out), and the salt used
+    pub async fn swap_tx(
         &mut self,
-        token_x: &Pubkey,
-        token_y: &Pubkey,
-        amount_x: u64,
-        amount_y: u64,
-        user: &Pubkey,
-    ) -> Result<VersionedTransaction> {
-        let is_x_sol = *token_x == SOL_MINT;
-        let is_y_sol = *token_y == SOL_MINT;
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        min_out: u64,
+        token_owner: &Pubkey,
+    ) -> Result<(VersionedTransaction, Pubkey, u64, [u8; 8])> {
+        let (plan, order_key, min_out, salt) = self
+            .swap_plan(token_in, token_out, amount_in, min_out, token_owner)
+            .await?;
 
-        let token_x_post_sol = if is_x_sol {
+        Ok((self.compile_plan(&plan).await?, order_key, min_out, salt))
+    }
+
+    /// Plan a swap, see `swap_tx`
+    ///
+    /// # Returns
+    /// Returns a `DarklakeTxPlan`, the order key, the minimum amount of output tokens expected (min_out), and the salt used
+    pub async fn swap_plan(
+        &mut self,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        min_out: u64,
+        token_owner: &Pubkey,
+    ) -> Result<(DarklakeTxPlan, Pubkey, u64, [u8; 8])> {
+        let is_from_sol = *token_in == SOL_MINT;
+        let is_to_sol = *token_out == SOL_MINT;
+
+        let _token_in = if is_from_sol {
             native_mint::ID
         } else {
-            token_x.clone()
+            token_in.clone()
         };
-        let token_y_post_sol = if is_y_sol {
+        let _token_out = if is_to_sol {
             native_mint::ID
         } else {
-            token_y.clone()
-        };
-
-        // used to sort token mints
-        let (_pool_key, _token_x, _token_y) =
-            Self::get_pool_address(&token_x_post_sol, &token_y_post_sol);
-
-        let (amount_x, amount_y) = if _token_x != token_x_post_sol {
-            (amount_y, amount_x)
-        } else {
-            (amount_x, amount_y)
+            token_out.clone()
         };
 
-        let token_x_account = self.rpc_client.get_account(&_token_x).await?;
-        let token_y_account = self.rpc_client.get_account(&_token_y).await?;
+        let (pool_key, _token_x, _token_y) = self.get_pool_address(&_token_in, &_token_out);
 
-        let initialize_pool_params = InitializePoolParamsIx {
-            user: user.clone(),
-            token_x: _token_x,
-            token_x_program: token_x_account.owner,
-            token_y: _token_y,
-            token_y_program: token_y_account.owner,
-            amount_x,
-            amount_y,
-        };
-
-        let compute_budget_ix: Instruction =
-            ComputeBudgetInstruction::set_compute_unit_limit(500_000);
+        if self.darklake_amm.key() != pool_key {
+            self.load_pool(&_token_x, &_token_y).await?;
+        }
 
-        let initialize_pool_instruction = self.initialize_pool_ix(&initialize_pool_params).await?;
+        self.update_accounts().await?;
 
-        let mut instructions = vec![compute_budget_ix];
-        if is_x_sol {
-            let sol_to_wsol_instructions = get_wrap_sol_to_wsol_instructions(user, amount_x)?;
-            instructions.push(sol_to_wsol_instructions[0].clone());
-            instructions.push(sol_to_wsol_instructions[1].clone());
-            instructions.push(sol_to_wsol_instructions[2].clone());
-        } else if is_y_sol {
-            let sol_to_wsol_instructions = get_wrap_sol_to_wsol_instructions(user, amount_y)?;
-            instructions.push(sol_to_wsol_instructions[0].clone());
-            instructions.push(sol_to_wsol_instructions[1].clone());
-            instructions.push(sol_to_wsol_instructions[2].clone());
+        let mut setup_instructions = vec![];
+
+        // an expired order can be slashed in the same transaction, anything else fails the swap
+        if let Err(e) = self.check_swap_preflight(token_owner).await {
+            match e.downcast_ref::<SwapPreflightError>() {
+                Some(SwapPreflightError::OrderExists {
+                    deadline,
+                    is_expired: true,
+                    ..
+                }) if self.slash_expired_orders => {
+                    setup_instructions.push(
+                        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                            token_owner,
+                            token_owner,
+                            &native_mint::ID,
+                            &spl_token::ID,
+                        ),
+                    );
+                    setup_instructions.push(
+                        self.slash_ix(&SlashParamsIx {
+                            settle_signer: *token_owner,
+                            order_owner: *token_owner,
+                            deadline: *deadline,
+                            current_slot: self.get_current_slot().await?,
+                        })
+                        .await?,
+                    );
+                }
+                _ => return Err(e),
+            }
         }
 
-        instructions.push(initialize_pool_instruction);
+        let salt = generate_random_salt();
 
-        let address_lookup_table_account =
-            get_address_lookup_table(&self.rpc_client, self.is_devnet).await?;
+        let swap_params = SwapParams {
+            source_mint: _token_in,
+            destination_mint: _token_out,
+            token_transfer_authority: token_owner.clone(),
+            amount_in,
+            swap_mode: SwapMode::ExactIn,
+            min_out,
+            salt,
+            label: self.label,
+        };
 
-        let recent_blockhash = self.rpc_client.get_latest_blockhash().await?;
-        let message_v0 = v0::Message::try_compile(
-            &user,
-            &instructions,
-            &[address_lookup_table_account],
-            recent_blockhash,
-        )?;
+        let swap_instruction = self.build_swap_ix(&swap_params).await?;
 
-        let initialize_pool_transaction = VersionedTransaction {
-            signatures: vec![],
-            message: VersionedMessage::V0(message_v0),
-        };
+        if is_from_sol {
+            let sol_to_wsol_instructions =
+                get_wrap_sol_to_wsol_instructions(&token_owner, amount_in)?;
+            setup_instructions.push(sol_to_wsol_instructions[0].clone());
+            setup_instructions.push(sol_to_wsol_instructions[1].clone());
+            setup_instructions.push(sol_to_wsol_instructions[2].clone());
+        }
 
-        Ok(initialize_pool_transaction)
-    }
+        let swap_plan = self
+            .build_plan(
+                token_owner,
+                Some(self.tx_defaults.swap_compute_unit_limit),
+                setup_instructions,
+                swap_instruction,
+                vec![],
+            )
+            .await?;
 
-    // MANUAL HANDLING (these are prone to changes in the future)
+        let order_key = self.darklake_amm.get_order_pubkey(&token_owner)?;
 
-    // before calling swap_ix/finalize_ix/add_liquidity_ix/remove_liquidity_ix -
-    // load_pool has to be called at least once before usage and update_accounts before each function call
+        Ok((swap_plan, order_key, min_out, salt))
+    }
 
-    /// Load pool data from the blockchain
+    /// Start a swap with the minimum output derived from a slippage tolerance
     ///
     /// # Arguments
-    /// * `token_x` - The first token mint address
-    /// * `token_y` - The second token mint address
+    /// * `token_in` - The input token mint
+    /// * `token_out` - The output token mint
+    /// * `amount_in` - The amount of input tokens
+    /// * `slippage_bps` - The accepted slippage in basis points (50 = 0.5%)
+    /// * `token_owner` - The token owner public key
     ///
     /// # Returns
-    /// Returns a tuple of (pool_key, sorted_token_x, sorted_token_y)
-    pub async fn load_pool(
+    /// Returns a `VersionedTransaction`, the order key, the minimum amount of output tokens expected (min_out), the salt used and the quote min_out was derived from
+    pub async fn swap_tx_with_slippage(
         &mut self,
-        token_x: &Pubkey,
-        token_y: &Pubkey,
-    ) -> Result<(Pubkey, Pubkey, Pubkey)> {
-        let (pool_key, _, _) = Self::get_pool_address(token_x, token_y);
-
-        let pool_account_data = self
-            .rpc_client
-            .get_account(&pool_key)
-            .await
-            .map_err(|_| anyhow::anyhow!("Pool not found"))?;
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        slippage_bps: u16,
+        token_owner: &Pubkey,
+    ) -> Result<(VersionedTransaction, Pubkey, u64, [u8; 8], Quote)> {
+        let quote = self.quote(token_in, token_out, amount_in).await?;
 
-        let pool_key_and_account = KeyedAccount {
-            key: pool_key,
-            account: AccountData {
-                data: pool_account_data.data.to_vec(),
-                owner: DARKLAKE_PROGRAM_ID,
-            },
-        };
+        let min_out = get_min_out_with_slippage(quote.out_amount, slippage_bps)?;
 
-        self.darklake_amm = DarklakeAmm::load_pool(&pool_key_and_account)?;
+        let (swap_transaction, order_key, min_out, salt) = self
+            .swap_tx(token_in, token_out, amount_in, min_out, token_owner)
+            .await?;
 
-        // returns sorted token mints
-        Ok((pool_key, token_x.clone(), token_y.clone()))
+        Ok((swap_transaction, order_key, min_out, salt, quote))
     }
 
-    /// Update account data from the blockchain
+    /// Finalize a swap order by settling, canceling, or slashing it
     ///
-    /// This function fetches the latest account data for all accounts that need to be updated
-    /// and updates the internal AMM state accordingly.
+    /// # Arguments
+    /// * `order_key` - The public key of the order to finalize
+    /// * `unwrap_wsol` - Whether to unwrap WSOL to SOL after settlement
+    /// * `min_out` - The minimum output amount expected (same as swap)
+    /// * `salt` - The salt used in the original swap (same as swap)
+    /// * `settle_signer` - Optional signer for settlement (defaults to order owner)
     ///
     /// # Returns
-    /// Returns `Ok(())` on success
-    pub async fn update_accounts(&mut self) -> Result<()> {
-        let accounts_to_update = self.darklake_amm.get_accounts_to_update();
-        let mut account_map = HashMap::new();
-        for account_key in accounts_to_update {
-            let account = self.rpc_client.get_account(&account_key).await?;
-            account_map.insert(
-                account_key,
-                AccountData {
-                    data: account.data,
-                    owner: account.owner,
-                },
+    /// Returns a `VersionedTransaction` ready to be signed and sent
+    pub async fn finalize_tx(
+        &mut self,
+        order_key: &Pubkey,
+        unwrap_wsol: bool,
+        min_out: u64,
+        salt: [u8; 8],
+        settle_signer: Option<&Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        let plan = self
+            .finalize_plan(order_key, unwrap_wsol, min_out, salt, settle_signer)
+            .await?;
+
+        self.compile_plan(&plan).await
+    }
+
+    /// Plan the finalization of a swap order, see `finalize_tx`
+    pub async fn finalize_plan(
+        &mut self,
+        order_key: &Pubkey,
+        unwrap_wsol: bool,
+        min_out: u64,
+        salt: [u8; 8],
+        settle_signer: Option<&Pubkey>,
+    ) -> Result<DarklakeTxPlan> {
+        let order = self.prepare_order(order_key).await?;
+
+        let settler = *settle_signer.unwrap_or(&order.trader);
+
+        let finalize_params = FinalizeParamsIx {
+            settle_signer: settler,    // who settles the order
+            order_owner: order.trader, // who owns the order
+            unwrap_wsol,               // Set to true if you want to unwrap WSOL to SOL
+            min_out,                   // Same min_out as swap
+            salt,                      // Same salt as swap
+            output: order.d_out,       // order prop
+            commitment: order.c_min,   // order prop
+            deadline: order.deadline,  // order prop
+            current_slot: self.get_current_slot().await?,
+        };
+
+        let finalize_instruction = self.finalize_ix(&finalize_params).await?;
+
+        self.build_finalize_plan(&settler, finalize_instruction)
+            .await
+    }
+
+    /// Settle a swap order
+    ///
+    /// Unlike `finalize_tx` the action is not inferred, the transaction fails to build if the
+    /// order cannot be settled (expired or `min_out` > output).
+    ///
+    /// # Arguments
+    /// * `order_key` - The public key of the order to settle
+    /// * `unwrap_wsol` - Whether to unwrap WSOL to SOL after settlement
+    /// * `min_out` - The minimum output amount expected (same as swap)
+    /// * `salt` - The salt used in the original swap (same as swap)
+    /// * `settle_signer` - Optional signer for settlement (defaults to order owner)
+    ///
+    /// # Returns
+    /// Returns a `VersionedTransaction` ready to be signed and sent
+    pub async fn settle_tx(
+        &mut self,
+        order_key: &Pubkey,
+        unwrap_wsol: bool,
+        min_out: u64,
+        salt: [u8; 8],
+        settle_signer: Option<&Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        let plan = self
+            .settle_plan(order_key, unwrap_wsol, min_out, salt, settle_signer)
+            .await?;
+
+        self.compile_plan(&plan).await
+    }
+
+    /// Plan the settlement of a swap order, see `settle_tx`
+    pub async fn settle_plan(
+        &mut self,
+        order_key: &Pubkey,
+        unwrap_wsol: bool,
+        min_out: u64,
+        salt: [u8; 8],
+        settle_signer: Option<&Pubkey>,
+    ) -> Result<DarklakeTxPlan> {
+        let order = self.prepare_order(order_key).await?;
+
+        let settler = *settle_signer.unwrap_or(&order.trader);
+
+        let settle_params = SettleParamsIx {
+            settle_signer: settler,
+            order_owner: order.trader,
+            unwrap_wsol,
+            min_out,
+            salt,
+            output: order.d_out,
+            commitment: order.c_min,
+            deadline: order.deadline,
+            current_slot: self.get_current_slot().await?,
+        };
+
+        let settle_instruction = self.settle_ix(&settle_params).await?;
+
+        self.build_finalize_plan(&settler, settle_instruction).await
+    }
+
+    /// Cancel a swap order
+    ///
+    /// Unlike `finalize_tx` the action is not inferred, the transaction fails to build if the
+    /// order cannot be cancelled (expired or `min_out` <= output).
+    ///
+    /// # Arguments
+    /// * `order_key` - The public key of the order to cancel
+    /// * `min_out` - The minimum output amount expected (same as swap)
+    /// * `salt` - The salt used in the original swap (same as swap)
+    /// * `settle_signer` - Optional signer for cancellation (defaults to order owner)
+    ///
+    /// # Returns
+    /// Returns a `VersionedTransaction` ready to be signed and sent
+    pub async fn cancel_tx(
+        &mut self,
+        order_key: &Pubkey,
+        min_out: u64,
+        salt: [u8; 8],
+        settle_signer: Option<&Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        let plan = self
+            .cancel_plan(order_key, min_out, salt, settle_signer)
+            .await?;
+
+        self.compile_plan(&plan).await
+    }
+
+    /// Plan the cancellation of a swap order, see `cancel_tx`
+    pub async fn cancel_plan(
+        &mut self,
+        order_key: &Pubkey,
+        min_out: u64,
+        salt: [u8; 8],
+        settle_signer: Option<&Pubkey>,
+    ) -> Result<DarklakeTxPlan> {
+        let order = self.prepare_order(order_key).await?;
+
+        let settler = *settle_signer.unwrap_or(&order.trader);
+
+        let cancel_params = CancelParamsIx {
+            settle_signer: settler,
+            order_owner: order.trader,
+            min_out,
+            salt,
+            output: order.d_out,
+            commitment: order.c_min,
+            deadline: order.deadline,
+            current_slot: self.get_current_slot().await?,
+        };
+
+        let cancel_instruction = self.cancel_ix(&cancel_params).await?;
+
+        self.build_finalize_plan(&settler, cancel_instruction).await
+    }
+
+    /// Slash an expired swap order
+    ///
+    /// Does not require `min_out` or `salt`, so any keeper can slash an order once its deadline
+    /// has passed.
+    ///
+    /// # Arguments
+    /// * `order_key` - The public key of the order to slash
+    /// * `settle_signer` - Optional signer for slashing (defaults to order owner)
+    ///
+    /// # Returns
+    /// Returns a `VersionedTransaction` ready to be signed and sent
+    pub async fn slash_tx(
+        &mut self,
+        order_key: &Pubkey,
+        settle_signer: Option<&Pubkey>,
+    ) -> Result<VersionedTransaction> {
+        let plan = self.slash_plan(order_key, settle_signer).await?;
+
+        self.compile_plan(&plan).await
+    }
+
+    /// Plan the slash of an expired swap order, see `slash_tx`
+    pub async fn slash_plan(
+        &mut self,
+        order_key: &Pubkey,
+        settle_signer: Option<&Pubkey>,
+    ) -> Result<DarklakeTxPlan> {
+        let order = self.prepare_order(order_key).await?;
+
+        let settler = *settle_signer.unwrap_or(&order.trader);
+
+        let slash_params = SlashParamsIx {
+            settle_signer: settler,
+            order_owner: order.trader,
+            deadline: order.deadline,
+            current_slot: self.get_current_slot().await?,
+        };
+
+        let slash_instruction = self.slash_ix(&slash_params).await?;
+
+        self.build_finalize_plan(&settler, slash_instruction).await
+    }
+
+    /// Add liquidity to a pool
+    ///
+    /// # Arguments
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
+    /// * `max_amount_x` - Maximum amount of token_x to add
+    /// * `max_amount_y` - Maximum amount of token_y to add
+    /// * `amount_lp` - Amount of LP tokens to mint
+    /// * `user` - The user's public key
+    ///
+    /// # Returns
+    /// Returns a `VersionedTransaction` ready to be signed and sent
+    pub async fn add_liquidity_tx(
+        &mut self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+        max_amount_x: u64,
+        max_amount_y: u64,
+        amount_lp: u64,
+        user: &Pubkey,
+    ) -> Result<VersionedTransaction> {
+        let plan = self
+            .add_liquidity_plan(
+                token_x,
+                token_y,
+                max_amount_x,
+                max_amount_y,
+                amount_lp,
+                user,
+            )
+            .await?;
+
+        self.compile_plan(&plan).await
+    }
+
+    /// Plan a liquidity deposit, see `add_liquidity_tx`
+    pub async fn add_liquidity_plan(
+        &mut self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+        max_amount_x: u64,
+        max_amount_y: u64,
+        amount_lp: u64,
+        user: &Pubkey,
+    ) -> Result<DarklakeTxPlan> {
+        let is_x_sol = *token_x == SOL_MINT;
+        let is_y_sol = *token_y == SOL_MINT;
+
+        let token_x_post_sol = if is_x_sol {
+            native_mint::ID
+        } else {
+            token_x.clone()
+        };
+        let token_y_post_sol = if is_y_sol {
+            native_mint::ID
+        } else {
+            token_y.clone()
+        };
+
+        let (pool_key, _token_x, _token_y) =
+            self.get_pool_address(&token_x_post_sol, &token_y_post_sol);
+
+        // swaps ammount of token_x and token_y if tokens are not sorted
+        let (max_amount_x, max_amount_y) = if _token_x != token_x_post_sol {
+            (max_amount_y, max_amount_x)
+        } else {
+            (max_amount_x, max_amount_y)
+        };
+
+        if self.darklake_amm.key() != pool_key {
+            self.load_pool(&_token_x, &_token_y).await?;
+        }
+
+        self.update_accounts().await?;
+
+        let add_liquidity_params = AddLiquidityParamsIx {
+            amount_lp,
+            max_amount_x,
+            max_amount_y,
+            user: user.clone(),
+        };
+
+        let add_liquidity_instruction = self.add_liquidity_ix(&add_liquidity_params).await?;
+
+        let mut setup_instructions = vec![];
+        if is_x_sol {
+            let sol_to_wsol_instructions = get_wrap_sol_to_wsol_instructions(&user, max_amount_x)?;
+            setup_instructions.push(sol_to_wsol_instructions[0].clone());
+            setup_instructions.push(sol_to_wsol_instructions[1].clone());
+            setup_instructions.push(sol_to_wsol_instructions[2].clone());
+        } else if is_y_sol {
+            let sol_to_wsol_instructions = get_wrap_sol_to_wsol_instructions(&user, max_amount_y)?;
+            setup_instructions.push(sol_to_wsol_instructions[0].clone());
+            setup_instructions.push(sol_to_wsol_instructions[1].clone());
+            setup_instructions.push(sol_to_wsol_instructions[2].clone());
+        }
+
+        self.build_plan(
+            user,
+            None,
+            setup_instructions,
+            add_liquidity_instruction,
+            vec![],
+        )
+        .await
+    }
+
+    /// Check that a user can fund a liquidity deposit before building it
+    ///
+    /// Fetches the user's x, y and LP token accounts and SOL balance. A SOL side is wrapped in the
+    /// deposit transaction so it only needs lamports, a missing LP token account is created by the
+    /// program and needs rent.
+    ///
+    /// # Arguments
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
+    /// * `max_amount_x` - Maximum amount of token_x to deposit
+    /// * `max_amount_y` - Maximum amount of token_y to deposit
+    /// * `user` - The user's public key
+    ///
+    /// # Returns
+    /// Returns every `PreflightIssue` found, empty if the deposit can be funded
+    pub async fn preflight_add_liquidity(
+        &mut self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+        max_amount_x: u64,
+        max_amount_y: u64,
+        user: &Pubkey,
+    ) -> Result<Vec<PreflightIssue>> {
+        let is_x_sol = *token_x == SOL_MINT;
+        let is_y_sol = *token_y == SOL_MINT;
+
+        let token_x_post_sol = if is_x_sol { native_mint::ID } else { *token_x };
+        let token_y_post_sol = if is_y_sol { native_mint::ID } else { *token_y };
+
+        let (pool_key, _token_x, _token_y) =
+            self.get_pool_address(&token_x_post_sol, &token_y_post_sol);
+
+        if self.darklake_amm.key() != pool_key {
+            self.load_pool(&_token_x, &_token_y).await?;
+        }
+
+        self.update_accounts().await?;
+
+        let mut token_balances = vec![];
+        let mut required_lamports: u64 = 0;
+
+        for (token, is_sol, max_amount) in [
+            (token_x_post_sol, is_x_sol, max_amount_x),
+            (token_y_post_sol, is_y_sol, max_amount_y),
+        ] {
+            let (token_account, balance) = self.get_user_token_balance(user, &token).await?;
+            if is_sol {
+                required_lamports = required_lamports.saturating_add(max_amount);
+                if balance.is_none() {
+                    let rent = self.get_token_account_rent(&token).await?;
+                    required_lamports = required_lamports.saturating_add(rent);
+                }
+            } else {
+                token_balances.push((token, token_account, balance, max_amount));
+            }
+        }
+
+        let token_mint_lp = DarklakeAmm::get_token_mint_lp(&self.network.program_id, pool_key);
+        let (_, balance_lp) = self.get_user_token_balance(user, &token_mint_lp).await?;
+        if balance_lp.is_none() {
+            let rent = self.get_token_account_rent(&token_mint_lp).await?;
+            required_lamports = required_lamports.saturating_add(rent);
+        }
+
+        let sol_balance = self.get_sol_balance(user).await?;
+
+        Ok(get_preflight_issues(
+            &token_balances,
+            sol_balance,
+            required_lamports,
+        ))
+    }
+
+    /// Remove liquidity from a pool
+    ///
+    /// # Arguments
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
+    /// * `min_amount_x` - Minimum amount of token_x to receive
+    /// * `min_amount_y` - Minimum amount of token_y to receive
+    /// * `amount_lp` - Amount of LP tokens to burn
+    /// * `user` - The user's public key
+    ///
+    /// # Returns
+    /// Returns a `VersionedTransaction` ready to be signed and sent
+    pub async fn remove_liquidity_tx(
+        &mut self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+        min_amount_x: u64,
+        min_amount_y: u64,
+        amount_lp: u64,
+        user: &Pubkey,
+    ) -> Result<VersionedTransaction> {
+        let plan = self
+            .remove_liquidity_plan(
+                token_x,
+                token_y,
+                min_amount_x,
+                min_amount_y,
+                amount_lp,
+                user,
+            )
+            .await?;
+
+        self.compile_plan(&plan).await
+    }
+
+    /// Plan a liquidity withdrawal, see `remove_liquidity_tx`
+    pub async fn remove_liquidity_plan(
+        &mut self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+        min_amount_x: u64,
+        min_amount_y: u64,
+        amount_lp: u64,
+        user: &Pubkey,
+    ) -> Result<DarklakeTxPlan> {
+        let is_x_sol = *token_x == SOL_MINT;
+        let is_y_sol = *token_y == SOL_MINT;
+
+        let token_x_post_sol = if is_x_sol {
+            native_mint::ID
+        } else {
+            token_x.clone()
+        };
+        let token_y_post_sol = if is_y_sol {
+            native_mint::ID
+        } else {
+            token_y.clone()
+        };
+
+        let (pool_key, _token_x, _token_y) =
+            self.get_pool_address(&token_x_post_sol, &token_y_post_sol);
+
+        let (min_amount_x, min_amount_y) = if _token_x != token_x_post_sol {
+            (min_amount_y, min_amount_x)
+        } else {
+            (min_amount_x, min_amount_y)
+        };
+
+        if self.darklake_amm.key() != pool_key {
+            self.load_pool(&_token_x, &_token_y).await?;
+        }
+
+        self.update_accounts().await?;
+
+        let (token_x_owner, token_y_owner) = self.darklake_amm.get_token_owners();
+
+        // make sure the user has the token accounts
+        let create_token_x_ata_ix =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                user,
+                user,
+                &_token_x,
+                &token_x_owner,
+            );
+
+        let create_token_y_ata_ix =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                user,
+                user,
+                &_token_y,
+                &token_y_owner,
+            );
+
+        let remove_liquidity_params = RemoveLiquidityParamsIx {
+            amount_lp,
+            min_amount_x,
+            min_amount_y,
+            user: user.clone(),
+        };
+
+        let remove_liquidity_instruction =
+            self.remove_liquidity_ix(&remove_liquidity_params).await?;
+
+        let setup_instructions = vec![create_token_x_ata_ix, create_token_y_ata_ix];
+
+        // Add close WSOL instructions if either token is SOL (user can't have multiple WSOL accounts)
+        let mut cleanup_instructions = vec![];
+        if is_x_sol || is_y_sol {
+            let close_wsol_instructions = get_close_wsol_instructions(user)?;
+            cleanup_instructions.push(close_wsol_instructions[0].clone());
+            cleanup_instructions.push(close_wsol_instructions[1].clone());
+        }
+
+        self.build_plan(
+            user,
+            None,
+            setup_instructions,
+            remove_liquidity_instruction,
+            cleanup_instructions,
+        )
+        .await
+    }
+
+    /// Initialize a new liquidity pool
+    ///
+    /// # Arguments
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
+    /// * `amount_x` - Initial amount of token_x to add
+    /// * `amount_y` - Initial amount of token_y to add
+    /// * `user` - The user's public key
+    ///
+    /// # Returns
+    /// Returns a `VersionedTransaction` ready to be signed and sent
+    pub async fn initialize_pool_tx(
+        &mut self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+        amount_x: u64,
+        amount_y: u64,
+        user: &Pubkey,
+    ) -> Result<VersionedTransaction> {
+        let plan = self
+            .initialize_pool_plan(token_x, token_y, amount_x, amount_y, user)
+            .await?;
+
+        self.compile_plan(&plan).await
+    }
+
+    /// Plan the initialization of a pool, see `initialize_pool_tx`
+    pub async fn initialize_pool_plan(
+        &mut self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+        amount_x: u64,
+        amount_y: u64,
+        user: &Pubkey,
+    ) -> Result<DarklakeTxPlan> {
+        let is_x_sol = *token_x == SOL_MINT;
+        let is_y_sol = *token_y == SOL_MINT;
+
+        let token_x_post_sol = if is_x_sol {
+            native_mint::ID
+        } else {
+            token_x.clone()
+        };
+        let token_y_post_sol = if is_y_sol {
+            native_mint::ID
+        } else {
+            token_y.clone()
+        };
+
+        // used to sort token mints
+        let (_pool_key, _token_x, _token_y) =
+            self.get_pool_address(&token_x_post_sol, &token_y_post_sol);
+
+        let (amount_x, amount_y) = if _token_x != token_x_post_sol {
+            (amount_y, amount_x)
+        } else {
+            (amount_x, amount_y)
+        };
+
+        let token_x_account = self
+            .chain
+            .get_account(&_token_x)
+            .await?
+            .with_context(|| format!("Mint {} not found", _token_x))?;
+        let token_y_account = self
+            .chain
+            .get_account(&_token_y)
+            .await?
+            .with_context(|| format!("Mint {} not found", _token_y))?;
+
+        let initialize_pool_params = InitializePoolParamsIx {
+            user: user.clone(),
+            token_x: _token_x,
+            token_x_program: token_x_account.owner,
+            token_y: _token_y,
+            token_y_program: token_y_account.owner,
+            amount_x,
+            amount_y,
+        };
+
+        let initialize_pool_instruction = self.initialize_pool_ix(&initialize_pool_params).await?;
+
+        let mut setup_instructions = vec![];
+        if is_x_sol {
+            let sol_to_wsol_instructions = get_wrap_sol_to_wsol_instructions(user, amount_x)?;
+            setup_instructions.push(sol_to_wsol_instructions[0].clone());
+            setup_instructions.push(sol_to_wsol_instructions[1].clone());
+            setup_instructions.push(sol_to_wsol_instructions[2].clone());
+        } else if is_y_sol {
+            let sol_to_wsol_instructions = get_wrap_sol_to_wsol_instructions(user, amount_y)?;
+            setup_instructions.push(sol_to_wsol_instructions[0].clone());
+            setup_instructions.push(sol_to_wsol_instructions[1].clone());
+            setup_instructions.push(sol_to_wsol_instructions[2].clone());
+        }
+
+        self.build_plan(
+            user,
+            Some(self.tx_defaults.compute_unit_limit),
+            setup_instructions,
+            initialize_pool_instruction,
+            vec![],
+        )
+        .await
+    }
+
+    /// Find the best route between two tokens, directly or through one intermediate pool
+    ///
+    /// # Arguments
+    /// * `token_in` - The input token mint
+    /// * `token_out` - The output token mint
+    /// * `amount_in` - The amount of input tokens
+    /// * `intermediate_mints` - Mints to route through (defaults to WSOL and USDC)
+    ///
+    /// # Returns
+    /// Returns the `Route` with the largest output amount
+    pub async fn find_route(
+        &self,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        intermediate_mints: Option<&[Pubkey]>,
+    ) -> Result<Route> {
+        let _token_in = if *token_in == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_in
+        };
+        let _token_out = if *token_out == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_out
+        };
+
+        let default_intermediate_mints = [native_mint::ID, self.network.usdc_mint];
+        let intermediate_mints = intermediate_mints.unwrap_or(&default_intermediate_mints);
+
+        let paths = get_candidate_paths(&_token_in, &_token_out, intermediate_mints);
+        let pools = self
+            .load_route_pools(&get_path_pool_keys(
+                &self.network.program_id,
+                &self.amm_config,
+                &paths,
+            ))
+            .await?;
+
+        get_best_route(
+            &self.network.program_id,
+            &self.amm_config,
+            token_in,
+            token_out,
+            amount_in,
+            &paths,
+            &pools,
+        )
+    }
+
+    /// Execute a route hop by hop
+    ///
+    /// Each hop is a commit/settle pair, so hops are sent sequentially: swap, settle, then the
+    /// next hop swaps the settled order's `actual_out` less the intermediate token's transfer fee.
+    /// If a hop cannot be settled its order is cancelled and execution stops, leaving the funds of
+    /// the previous hop in the intermediate token. Routes from `find_route` have at most one
+    /// intermediate mint (two hops).
+    ///
+    /// # Arguments
+    /// * `route` - The route returned by `find_route`
+    /// * `slippage_bps` - The accepted slippage per hop in basis points
+    /// * `signer` - The token owner, signs and pays for every transaction
+    ///
+    /// # Returns
+    /// Returns the signatures of all sent transactions in order
+    pub async fn execute_route(
+        &mut self,
+        route: &Route,
+        slippage_bps: u16,
+        signer: &Keypair,
+    ) -> Result<Vec<Signature>> {
+        let owner = signer.pubkey();
+        let mut signatures = Vec::new();
+        let mut amount_in = route.amount_in;
+
+        for (index, hop) in route.hops.iter().enumerate() {
+            let is_first_hop = index == 0;
+            let is_last_hop = index == route.hops.len() - 1;
+
+            let token_in = if is_first_hop {
+                route.token_in
+            } else {
+                hop.token_in
+            };
+            let token_out = if is_last_hop {
+                route.token_out
+            } else {
+                hop.token_out
+            };
+
+            let (swap_transaction, order_key, min_out, salt, _) = self
+                .swap_tx_with_slippage(&token_in, &token_out, amount_in, slippage_bps, &owner)
+                .await?;
+            signatures.push(self.sign_and_send(swap_transaction, signer).await?);
+
+            // the balance change of the intermediate token is not the output when it is WSOL, the
+            // refunded `wsol_trade_deposit` lands in the same account
+            let order = self.prepare_order(&order_key).await?;
+            let output_transfer_fee_config = if order.is_x_to_y {
+                self.darklake_amm.token_y_transfer_fee_config
+            } else {
+                self.darklake_amm.token_x_transfer_fee_config
+            };
+
+            let unwrap_wsol = is_last_hop && route.token_out == SOL_MINT;
+            self.settle_or_cancel(
+                &order_key,
+                unwrap_wsol,
+                min_out,
+                salt,
+                signer,
+                &mut signatures,
+            )
+            .await
+            .with_context(|| format!("Hop {} failed", index + 1))?;
+
+            if !is_last_hop {
+                let epoch = self.chain.get_epoch().await?;
+                amount_in = order.actual_out.saturating_sub(get_transfer_fee(
+                    output_transfer_fee_config,
+                    order.actual_out,
+                    epoch,
+                )?);
+
+                if amount_in == 0 {
+                    bail!("Hop {} settled without output", index + 1);
+                }
+            }
+        }
+
+        Ok(signatures)
+    }
+
+    /// Quote a single token liquidity deposit (zap)
+    ///
+    /// # Arguments
+    /// * `token_in` - The token deposited
+    /// * `token_other` - The other token of the pool
+    /// * `amount_in` - The amount of `token_in` to deposit
+    ///
+    /// # Returns
+    /// Returns a `ZapQuote` with the part of `amount_in` to swap and the expected deposit
+    pub async fn quote_zap(
+        &mut self,
+        token_in: &Pubkey,
+        token_other: &Pubkey,
+        amount_in: u64,
+    ) -> Result<ZapQuote> {
+        let _token_in = if *token_in == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_in
+        };
+        let _token_other = if *token_other == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_other
+        };
+
+        let (pool_key, _token_x, _token_y) = self.get_pool_address(&_token_in, &_token_other);
+
+        if self.darklake_amm.key() != pool_key {
+            self.load_pool(&_token_x, &_token_y).await?;
+        }
+
+        self.update_accounts().await?;
+
+        let epoch = self.chain.get_epoch().await?;
+
+        self.darklake_amm.quote_zap(&_token_in, amount_in, epoch)
+    }
+
+    /// Add liquidity from a single token (zap)
+    ///
+    /// Swaps the part of `amount_in` given by `quote_zap` into the other token, settles the swap
+    /// (cancelling it and stopping if it cannot be settled), then deposits the remaining input
+    /// together with the settled order's `actual_out` less the transfer fee. The swap output is
+    /// kept as WSOL when the other token is SOL so it can be deposited directly.
+    ///
+    /// # Arguments
+    /// * `token_in` - The token deposited
+    /// * `token_other` - The other token of the pool
+    /// * `amount_in` - The amount of `token_in` to deposit
+    /// * `slippage_bps` - The accepted slippage of the swap in basis points
+    /// * `signer` - The depositor, signs and pays for every transaction
+    ///
+    /// # Returns
+    /// Returns the signatures of all sent transactions in order
+    pub async fn execute_zap(
+        &mut self,
+        token_in: &Pubkey,
+        token_other: &Pubkey,
+        amount_in: u64,
+        slippage_bps: u16,
+        signer: &Keypair,
+    ) -> Result<Vec<Signature>> {
+        let owner = signer.pubkey();
+        let mut signatures = Vec::new();
+
+        let zap_quote = self.quote_zap(token_in, token_other, amount_in).await?;
+
+        // the swap output stays wrapped so it can be deposited as is
+        let token_other_post_sol = if *token_other == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_other
+        };
+
+        let (swap_transaction, order_key, min_out, salt, _) = self
+            .swap_tx_with_slippage(
+                token_in,
+                &token_other_post_sol,
+                zap_quote.swap_amount,
+                slippage_bps,
+                &owner,
+            )
+            .await?;
+        signatures.push(self.sign_and_send(swap_transaction, signer).await?);
+
+        // the balance change of the other token is not the output when it is WSOL, the refunded
+        // `wsol_trade_deposit` lands in the same account
+        let order = self.prepare_order(&order_key).await?;
+        let output_transfer_fee_config = if order.is_x_to_y {
+            self.darklake_amm.token_y_transfer_fee_config
+        } else {
+            self.darklake_amm.token_x_transfer_fee_config
+        };
+
+        self.settle_or_cancel(&order_key, false, min_out, salt, signer, &mut signatures)
+            .await?;
+
+        let epoch = self.chain.get_epoch().await?;
+        let amount_other = order.actual_out.saturating_sub(get_transfer_fee(
+            output_transfer_fee_config,
+            order.actual_out,
+            epoch,
+        )?);
+        let amount_in_remaining = amount_in - zap_quote.swap_amount;
+
+        let liquidity_quote = self
+            .quote_add_liquidity(
+                token_in,
+                &token_other_post_sol,
+                &token_other_post_sol,
+                amount_other,
+            )
+            .await?;
+        let required_amount_in = if liquidity_quote.token_mint_x == token_other_post_sol {
+            liquidity_quote.amount_y
+        } else {
+            liquidity_quote.amount_x
+        };
+        let liquidity_quote = if required_amount_in > amount_in_remaining {
+            self.quote_add_liquidity(
+                token_in,
+                &token_other_post_sol,
+                token_in,
+                amount_in_remaining,
+            )
+            .await?
+        } else {
+            liquidity_quote
+        };
+
+        let add_liquidity_transaction = self
+            .add_liquidity_tx(
+                token_in,
+                &token_other_post_sol,
+                amount_in_remaining,
+                amount_other,
+                liquidity_quote.amount_lp,
+                &owner,
+            )
+            .await?;
+        signatures.push(
+            self.sign_and_send(add_liquidity_transaction, signer)
+                .await?,
+        );
+
+        Ok(signatures)
+    }
+
+    // MANUAL HANDLING (these are prone to changes in the future)
+
+    // before calling swap_ix/finalize_ix/add_liquidity_ix/remove_liquidity_ix -
+    // load_pool has to be called at least once before usage and update_accounts before each function call
+
+    /// Load pool data from the blockchain
+    ///
+    /// # Arguments
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
+    ///
+    /// # Returns
+    /// Returns a tuple of (pool_key, sorted_token_x, sorted_token_y)
+    pub async fn load_pool(
+        &mut self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+    ) -> Result<(Pubkey, Pubkey, Pubkey)> {
+        let (pool_key, _, _) = self.get_pool_address(token_x, token_y);
+
+        let pool_account_data = self
+            .chain
+            .get_account(&pool_key)
+            .await?
+            .context("Pool not found")?;
+
+        let pool_key_and_account = KeyedAccount {
+            key: pool_key,
+            account: AccountData {
+                data: pool_account_data.data.to_vec(),
+                owner: pool_account_data.owner,
+            },
+        };
+
+        self.darklake_amm =
+            DarklakeAmm::load_pool_with_network(&pool_key_and_account, self.network.clone())?;
+
+        // returns sorted token mints
+        Ok((pool_key, token_x.clone(), token_y.clone()))
+    }
+
+    /// Update account data from the blockchain
+    ///
+    /// This function fetches the latest account data for all accounts that need to be updated
+    /// and updates the internal AMM state accordingly.
+    ///
+    /// # Returns
+    /// Returns `Ok(())` on success
+    pub async fn update_accounts(&mut self) -> Result<()> {
+        let accounts_to_update = self.darklake_amm.get_accounts_to_update();
+        let mut account_map = HashMap::new();
+        for account_key in accounts_to_update {
+            let account = self
+                .chain
+                .get_account(&account_key)
+                .await?
+                .with_context(|| format!("Account {} not found", account_key))?;
+            account_map.insert(
+                account_key,
+                AccountData {
+                    data: account.data,
+                    owner: account.owner,
+                },
+            );
+        }
+        self.darklake_amm.update(&account_map)?;
+
+        Ok(())
+    }
+
+    /// Get order data for a user
+    ///
+    /// This function does not require load_pool or update_accounts and is a standalone function
+    /// that can be called after new() is called.
+    ///
+    /// # Arguments
+    /// * `user` - The user's public key
+    /// * `commitment_level` - The commitment level for the RPC call
+    ///
+    /// # Returns
+    /// Returns the `Order` data for the user
+    pub async fn get_order(
+        &self,
+        user: &Pubkey,
+        commitment_level: CommitmentLevel,
+    ) -> Result<Order> {
+        let order_key = self.darklake_amm.get_order_pubkey(user)?;
+
+        let order_data = self
+            .chain
+            .get_account_with_commitment(
+                &order_key,
+                CommitmentConfig {
+                    commitment: commitment_level,
+                },
+            )
+            .await?;
+        if order_data.is_none() {
+            return Err(anyhow::anyhow!("Order not found"));
+        }
+
+        let order_data = order_data.unwrap();
+
+        let order = Order::try_from_program_account_data(
+            &order_data.data,
+            &order_data.owner,
+            &self.network.program_id,
+        )?;
+
+        Ok(order)
+    }
+
+    /// List all Darklake pools
+    ///
+    /// This function does not require load_pool or update_accounts and is a standalone function
+    /// that can be called after new() is called.
+    ///
+    /// # Returns
+    /// Returns every `Pool` account with its address and reserve balances
+    pub async fn list_pools(&self) -> Result<Vec<PoolInfo>> {
+        let pool_accounts = self
+            .chain
+            .get_program_accounts(&self.network.program_id, &POOL_DISCRIMINATOR)
+            .await?;
+
+        let mut pools = Vec::new();
+        for (pool_key, pool_account) in pool_accounts {
+            match Pool::try_from_program_account_data(
+                &pool_account.data,
+                &pool_account.owner,
+                &self.network.program_id,
+            ) {
+                Ok(pool) => pools.push((pool_key, pool)),
+                Err(e) => log::warn!("Skipping pool {}: {}", pool_key, e),
+            }
+        }
+
+        self.get_pool_infos(pools).await
+    }
+
+    /// List every AmmConfig (fee tier) of the Darklake program
+    ///
+    /// # Returns
+    /// Returns an `AmmConfigInfo` with the address and decoded account of each AmmConfig
+    pub async fn list_amm_configs(&self) -> Result<Vec<AmmConfigInfo>> {
+        let amm_config_accounts = self
+            .chain
+            .get_program_accounts(&self.network.program_id, &AMM_CONFIG_DISCRIMINATOR)
+            .await?;
+
+        let mut amm_configs = Vec::new();
+        for (amm_config_key, amm_config_account) in amm_config_accounts {
+            match AmmConfig::try_from_program_account_data(
+                &amm_config_account.data,
+                &amm_config_account.owner,
+                &self.network.program_id,
+            ) {
+                Ok(amm_config) => amm_configs.push(AmmConfigInfo {
+                    key: amm_config_key,
+                    amm_config,
+                }),
+                Err(e) => log::warn!("Skipping AmmConfig {}: {}", amm_config_key, e),
+            }
+        }
+
+        Ok(amm_configs)
+    }
+
+    /// List the pools of a mint pair across every AmmConfig (fee tier)
+    ///
+    /// # Arguments
+    /// * `token_x` - The first token mint address
+    /// * `token_y` - The second token mint address
+    ///
+    /// # Returns
+    /// Returns each existing pool with its AmmConfig, sorted by trade fee rate. Pass the
+    /// AmmConfig key to `set_amm_config` to trade in that pool.
+    pub async fn list_pair_pools(
+        &self,
+        token_x: &Pubkey,
+        token_y: &Pubkey,
+    ) -> Result<Vec<(AmmConfigInfo, PoolInfo)>> {
+        let token_x_post_sol = if *token_x == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_x
+        };
+        let token_y_post_sol = if *token_y == SOL_MINT {
+            native_mint::ID
+        } else {
+            *token_y
+        };
+
+        let mut amm_configs = self.list_amm_configs().await?;
+        amm_configs.sort_by_key(|amm_config| amm_config.amm_config.trade_fee_rate);
+
+        let pool_keys: Vec<Pubkey> = amm_configs
+            .iter()
+            .map(|amm_config| {
+                get_pool_key(
+                    &self.network.program_id,
+                    &amm_config.key,
+                    &token_x_post_sol,
+                    &token_y_post_sol,
+                )
+            })
+            .collect();
+
+        let mut pair_pools = Vec::new();
+        for (amm_config_chunk, pool_key_chunk) in amm_configs.chunks(100).zip(pool_keys.chunks(100))
+        {
+            let pool_accounts = self.chain.get_multiple_accounts(pool_key_chunk).await?;
+            for ((amm_config, pool_key), pool_account) in amm_config_chunk
+                .iter()
+                .zip(pool_key_chunk)
+                .zip(pool_accounts)
+            {
+                let Some(pool_account) = pool_account else {
+                    continue;
+                };
+
+                match Pool::try_from_program_account_data(
+                    &pool_account.data,
+                    &pool_account.owner,
+                    &self.network.program_id,
+                ) {
+                    Ok(pool) => pair_pools.push((amm_config.clone(), (*pool_key, pool))),
+                    Err(e) => log::warn!("Skipping pool {}: {}", pool_key, e),
+                }
+            }
+        }
+
+        let (amm_configs, pools): (Vec<_>, Vec<_>) = pair_pools.into_iter().unzip();
+        let pool_infos = self.get_pool_infos(pools).await?;
+
+        Ok(amm_configs.into_iter().zip(pool_infos).collect())
+    }
+
+    /// Attach the reserve balances to decoded pools
+    async fn get_pool_infos(&self, pools: Vec<(Pubkey, Pool)>) -> Result<Vec<PoolInfo>> {
+        let reserve_keys: Vec<Pubkey> = pools
+            .iter()
+            .flat_map(|(_, pool)| [pool.reserve_x, pool.reserve_y])
+            .collect();
+
+        let mut reserve_balances = HashMap::new();
+        for chunk in reserve_keys.chunks(100) {
+            let reserve_accounts = self.chain.get_multiple_accounts(chunk).await?;
+            for (reserve_key, reserve_account) in chunk.iter().zip(reserve_accounts) {
+                let Some(reserve_account) = reserve_account else {
+                    continue;
+                };
+
+                match DarklakeAmm::parse_token_account_balance(
+                    &reserve_account.data,
+                    &reserve_account.owner,
+                    reserve_key,
+                ) {
+                    Ok(balance) => {
+                        reserve_balances.insert(*reserve_key, balance);
+                    }
+                    Err(e) => log::warn!("Skipping reserve {}: {}", reserve_key, e),
+                }
+            }
+        }
+
+        Ok(pools
+            .into_iter()
+            .map(|(key, pool)| PoolInfo {
+                key,
+                reserve_x_balance: reserve_balances.get(&pool.reserve_x).copied().unwrap_or(0),
+                reserve_y_balance: reserve_balances.get(&pool.reserve_y).copied().unwrap_or(0),
+                pool,
+            })
+            .collect())
+    }
+
+    /// Create a swap instruction
+    ///
+    /// Fails with a `SwapPreflightError` if the pool is halted or the trader already has an open
+    /// order in the pool.
+    ///
+    /// # Arguments
+    /// * `swap_params` - The swap parameters
+    ///
+    /// # Returns
+    /// Returns a `Instruction` ready to be added to a transaction
+    pub async fn swap_ix(&self, swap_params: &SwapParamsIx) -> Result<Instruction> {
+        self.check_swap_preflight(&swap_params.token_transfer_authority)
+            .await?;
+
+        let swap_params = SwapParams {
+            source_mint: swap_params.source_mint,
+            destination_mint: swap_params.destination_mint,
+            token_transfer_authority: swap_params.token_transfer_authority,
+            amount_in: swap_params.amount_in,
+            swap_mode: swap_params.swap_mode,
+            min_out: swap_params.min_out,
+            salt: swap_params.salt,
+            label: self.label,
+        };
+
+        self.build_swap_ix(&swap_params).await
+    }
+
+    /// Build a swap instruction without the preflight checks
+    async fn build_swap_ix(&self, swap_params: &SwapParams) -> Result<Instruction> {
+        let swap_and_account_metas = self
+            .darklake_amm
+            .get_swap_and_account_metas(swap_params)
+            .context("Failed to get swap instruction and account metadata")?;
+
+        self.with_transfer_hook_accounts(
+            Instruction {
+                program_id: self.network.program_id,
+                accounts: swap_and_account_metas.account_metas,
+                data: swap_and_account_metas.data,
+            },
+            &swap_params.token_transfer_authority,
+            &[(swap_params.source_mint, true, swap_params.amount_in)],
+        )
+        .await
+    }
+
+    /// Create a finalize instruction (settle, cancel or slash)
+    ///
+    /// The action is picked from the order state: slash if `current_slot` > `deadline`,
+    /// settle if `min_out` <= `output`, cancel otherwise.
+    ///
+    /// # Arguments
+    /// * `finalize_params` - The finalize parameters
+    ///
+    /// # Returns
+    /// Returns a `Instruction` ready to be added to a transaction
+    pub async fn finalize_ix(&self, finalize_params: &FinalizeParamsIx) -> Result<Instruction> {
+        let is_settle = finalize_params.min_out <= finalize_params.output;
+        let is_slash = finalize_params.current_slot > finalize_params.deadline;
+
+        if is_slash {
+            return self
+                .slash_ix(&SlashParamsIx {
+                    settle_signer: finalize_params.settle_signer,
+                    order_owner: finalize_params.order_owner,
+                    deadline: finalize_params.deadline,
+                    current_slot: finalize_params.current_slot,
+                })
+                .await;
+        }
+
+        if is_settle {
+            return self
+                .settle_ix(&SettleParamsIx {
+                    settle_signer: finalize_params.settle_signer,
+                    order_owner: finalize_params.order_owner,
+                    unwrap_wsol: finalize_params.unwrap_wsol,
+                    min_out: finalize_params.min_out,
+                    salt: finalize_params.salt,
+                    output: finalize_params.output,
+                    commitment: finalize_params.commitment,
+                    deadline: finalize_params.deadline,
+                    current_slot: finalize_params.current_slot,
+                })
+                .await;
+        }
+
+        self.cancel_ix(&CancelParamsIx {
+            settle_signer: finalize_params.settle_signer,
+            order_owner: finalize_params.order_owner,
+            min_out: finalize_params.min_out,
+            salt: finalize_params.salt,
+            output: finalize_params.output,
+            commitment: finalize_params.commitment,
+            deadline: finalize_params.deadline,
+            current_slot: finalize_params.current_slot,
+        })
+        .await
+    }
+
+    /// Create a settle instruction
+    ///
+    /// # Arguments
+    /// * `settle_params` - The settle parameters
+    ///
+    /// # Returns
+    /// Returns a `Instruction` ready to be added to a transaction
+    pub async fn settle_ix(&self, settle_params: &SettleParamsIx) -> Result<Instruction> {
+        let settle_params = SettleParams {
+            settle_signer: settle_params.settle_signer,
+            order_owner: settle_params.order_owner,
+            unwrap_wsol: settle_params.unwrap_wsol,
+            min_out: settle_params.min_out,
+            salt: settle_params.salt,
+            output: settle_params.output,
+            commitment: settle_params.commitment,
+            deadline: settle_params.deadline,
+            current_slot: settle_params.current_slot,
+            ref_code: self.ref_code,
+            label: self.label,
+        };
+
+        // fail before the (expensive) proof generation
+        if settle_params.current_slot > settle_params.deadline {
+            return Err(anyhow::anyhow!("Order has expired"));
+        }
+        if settle_params.min_out > settle_params.output {
+            return Err(anyhow::anyhow!("Cant settle this order, min_out > output"));
+        }
+
+        let order = self.load_order(&settle_params.order_owner).await?;
+        let token_mint_out = if order.is_x_to_y {
+            self.darklake_amm.pool.token_mint_y
+        } else {
+            self.darklake_amm.pool.token_mint_x
+        };
+
+        let proof_params = self
+            .generate_finalize_proof(
+                &self.settle_paths,
+                settle_params.min_out,
+                settle_params.salt,
+                settle_params.output,
+                settle_params.commitment,
+            )
+            .await?;
+
+        let settle_and_account_metas = self
+            .darklake_amm
+            .get_settle_and_account_metas(&settle_params, &proof_params)?;
+
+        self.with_transfer_hook_accounts(
+            Instruction {
+                program_id: self.network.program_id,
+                accounts: settle_and_account_metas.account_metas,
+                data: settle_and_account_metas.data,
+            },
+            &settle_params.order_owner,
+            &[(token_mint_out, false, settle_params.output)],
+        )
+        .await
+    }
+
+    /// Create a cancel instruction
+    ///
+    /// # Arguments
+    /// * `cancel_params` - The cancel parameters
+    ///
+    /// # Returns
+    /// Returns a `Instruction` ready to be added to a transaction
+    pub async fn cancel_ix(&self, cancel_params: &CancelParamsIx) -> Result<Instruction> {
+        let cancel_params = CancelParams {
+            settle_signer: cancel_params.settle_signer,
+            order_owner: cancel_params.order_owner,
+            min_out: cancel_params.min_out,
+            salt: cancel_params.salt,
+            output: cancel_params.output,
+            commitment: cancel_params.commitment,
+            deadline: cancel_params.deadline,
+            current_slot: cancel_params.current_slot,
+            label: self.label,
+        };
+
+        // fail before the (expensive) proof generation
+        if cancel_params.current_slot > cancel_params.deadline {
+            return Err(anyhow::anyhow!("Order has expired"));
+        }
+        if cancel_params.min_out <= cancel_params.output {
+            return Err(anyhow::anyhow!("Cant cancel this order, min_out <= output"));
+        }
+
+        let order = self.load_order(&cancel_params.order_owner).await?;
+
+        let proof_params = self
+            .generate_finalize_proof(
+                &self.cancel_paths,
+                cancel_params.min_out,
+                cancel_params.salt,
+                cancel_params.output,
+                cancel_params.commitment,
+            )
+            .await?;
+
+        let cancel_and_account_metas = self
+            .darklake_amm
+            .get_cancel_and_account_metas(&cancel_params, &proof_params)?;
+
+        self.with_transfer_hook_accounts(
+            Instruction {
+                program_id: self.network.program_id,
+                accounts: cancel_and_account_metas.account_metas,
+                data: cancel_and_account_metas.data,
+            },
+            &cancel_params.order_owner,
+            &[self.get_refund_transfer(&order)],
+        )
+        .await
+    }
+
+    /// Create a slash instruction
+    ///
+    /// # Arguments
+    /// * `slash_params` - The slash parameters
+    ///
+    /// # Returns
+    /// Returns a `Instruction` ready to be added to a transaction
+    pub async fn slash_ix(&self, slash_params: &SlashParamsIx) -> Result<Instruction> {
+        let slash_and_account_metas =
+            self.darklake_amm
+                .get_slash_and_account_metas(&SlashParams {
+                    settle_signer: slash_params.settle_signer,
+                    order_owner: slash_params.order_owner,
+                    deadline: slash_params.deadline,
+                    current_slot: slash_params.current_slot,
+                    label: self.label,
+                })?;
+
+        let order = self.load_order(&slash_params.order_owner).await?;
+
+        self.with_transfer_hook_accounts(
+            Instruction {
+                program_id: self.network.program_id,
+                accounts: slash_and_account_metas.account_metas,
+                data: slash_and_account_metas.data,
+            },
+            &slash_params.order_owner,
+            &[self.get_refund_transfer(&order)],
+        )
+        .await
+    }
+
+    /// Create an add liquidity instruction
+    ///
+    /// # Arguments
+    /// * `add_liquidity_params` - The add liquidity parameters
+    ///
+    /// # Returns
+    /// Returns a `Instruction` ready to be added to a transaction
+    pub async fn add_liquidity_ix(
+        &self,
+        add_liquidity_params: &AddLiquidityParamsIx,
+    ) -> Result<Instruction> {
+        let add_liquidity_params = AddLiquidityParams {
+            amount_lp: add_liquidity_params.amount_lp,
+            max_amount_x: add_liquidity_params.max_amount_x,
+            max_amount_y: add_liquidity_params.max_amount_y,
+            user: add_liquidity_params.user,
+            label: self.label,
+            ref_code: self.ref_code,
+        };
+
+        let add_liquidity_and_account_metas = self
+            .darklake_amm
+            .get_add_liquidity_and_account_metas(&add_liquidity_params)?;
+
+        self.with_transfer_hook_accounts(
+            Instruction {
+                program_id: self.network.program_id,
+                accounts: add_liquidity_and_account_metas.account_metas,
+                data: add_liquidity_and_account_metas.data,
+            },
+            &add_liquidity_params.user,
+            &[
+                (
+                    self.darklake_amm.pool.token_mint_x,
+                    true,
+                    add_liquidity_params.max_amount_x,
+                ),
+                (
+                    self.darklake_amm.pool.token_mint_y,
+                    true,
+                    add_liquidity_params.max_amount_y,
+                ),
+            ],
+        )
+        .await
+    }
+
+    /// Create a remove liquidity instruction
+    ///
+    /// # Arguments
+    /// * `remove_liquidity_params` - The remove liquidity parameters
+    ///
+    /// # Returns
+    /// Returns a `Instruction` ready to be added to a transaction
+    pub async fn remove_liquidity_ix(
+        &self,
+        remove_liquidity_params: &RemoveLiquidityParamsIx,
+    ) -> Result<Instruction> {
+        let remove_liquidity_params = RemoveLiquidityParams {
+            amount_lp: remove_liquidity_params.amount_lp,
+            min_amount_x: remove_liquidity_params.min_amount_x,
+            min_amount_y: remove_liquidity_params.min_amount_y,
+            user: remove_liquidity_params.user,
+            label: self.label,
+        };
+
+        let remove_liquidity_and_account_metas = self
+            .darklake_amm
+            .get_remove_liquidity_and_account_metas(&remove_liquidity_params)?;
+
+        self.with_transfer_hook_accounts(
+            Instruction {
+                program_id: self.network.program_id,
+                accounts: remove_liquidity_and_account_metas.account_metas,
+                data: remove_liquidity_and_account_metas.data,
+            },
+            &remove_liquidity_params.user,
+            &[
+                (
+                    self.darklake_amm.pool.token_mint_x,
+                    false,
+                    remove_liquidity_params.min_amount_x,
+                ),
+                (
+                    self.darklake_amm.pool.token_mint_y,
+                    false,
+                    remove_liquidity_params.min_amount_y,
+                ),
+            ],
+        )
+        .await
+    }
+
+    /// Create an initialize pool instruction
+    ///
+    /// # Arguments
+    /// * `initialize_pool_params` - The initialize pool parameters
+    ///
+    /// # Returns
+    /// Returns a `Instruction` ready to be added to a transaction
+    pub async fn initialize_pool_ix(
+        &self,
+        initialize_pool_params: &InitializePoolParamsIx,
+    ) -> Result<Instruction> {
+        let initialize_pool_params = InitializePoolParams {
+            user: initialize_pool_params.user,
+            amm_config: self.amm_config,
+            token_x: initialize_pool_params.token_x,
+            token_x_program: initialize_pool_params.token_x_program,
+            token_y: initialize_pool_params.token_y,
+            token_y_program: initialize_pool_params.token_y_program,
+            amount_x: initialize_pool_params.amount_x,
+            amount_y: initialize_pool_params.amount_y,
+            label: self.label,
+        };
+
+        let initialize_pool_and_account_metas = self
+            .darklake_amm
+            .get_initialize_pool_and_account_metas(&initialize_pool_params)?;
+
+        Ok(Instruction {
+            program_id: self.network.program_id,
+            accounts: initialize_pool_and_account_metas.account_metas,
+            data: initialize_pool_and_account_metas.data,
+        })
+    }
+
+    /// Fetch an order (retrying while it is not yet visible) and sync the pool it belongs to
+    async fn prepare_order(&mut self, order_key: &Pubkey) -> Result<Order> {
+        // Retry getting order data 5 times every 5 seconds
+        let mut order_data = None;
+        for attempt in 1..=5 {
+            match self
+                .chain
+                .get_account(order_key)
+                .await
+                .and_then(|account| account.context("Order not found"))
+            {
+                Ok(account) => {
+                    order_data = Some(account);
+                    break;
+                }
+                Err(e) => {
+                    if attempt == 5 {
+                        return Err(e).context("Failed to get order data after 5 attempts");
+                    }
+                    log::warn!(
+                        "Attempt {} failed to get order data: {}. Retrying in 5 seconds...",
+                        attempt,
+                        e
+                    );
+                    sleep(Duration::from_secs(5)).await;
+                }
+            }
+        }
+
+        // Verify we got the order data
+        if order_data.is_none() {
+            return Err(anyhow::anyhow!(
+                "Failed to get order data after all retry attempts"
+            ));
+        }
+
+        let order_data = order_data.unwrap();
+        let order = Order::try_from_program_account_data(
+            &order_data.data,
+            &order_data.owner,
+            &self.network.program_id,
+        )?;
+
+        let (pool_key, _token_x, _token_y) =
+            self.get_pool_address(&order.token_mint_x, &order.token_mint_y);
+
+        if self.darklake_amm.key() != pool_key {
+            self.load_pool(&_token_x, &_token_y).await?;
+        }
+
+        self.update_accounts().await?;
+
+        if self.darklake_amm.get_order_pubkey(&order.trader)? != *order_key {
+            bail!(
+                "Order {} is not in the pool of AmmConfig {}, use set_amm_config to select its fee tier",
+                order_key,
+                self.amm_config
             );
         }
-        self.darklake_amm.update(&account_map)?;
 
-        Ok(())
+        Ok(order)
+    }
+
+    /// Fetch the pools with the given keys and build an offline quoting engine for each
+    async fn load_route_pools(
+        &self,
+        pool_keys: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, DarklakePool>> {
+        let pool_accounts = self.chain.get_multiple_accounts(pool_keys).await?;
+
+        let mut account_map = AccountMap::new();
+        let mut account_keys = Vec::new();
+        for (pool_key, pool_account) in pool_keys.iter().zip(pool_accounts) {
+            let Some(pool_account) = pool_account else {
+                continue;
+            };
+
+            let pool = match Pool::try_from_program_account_data(
+                &pool_account.data,
+                &pool_account.owner,
+                &self.network.program_id,
+            ) {
+                Ok(pool) => pool,
+                Err(e) => {
+                    log::warn!("Skipping pool {}: {}", pool_key, e);
+                    continue;
+                }
+            };
+
+            account_keys.extend(
+                DarklakePool::required_accounts(pool_key, &pool)
+                    .into_iter()
+                    .filter(|account_key| account_key != pool_key),
+            );
+            account_map.insert(
+                *pool_key,
+                AccountData {
+                    data: pool_account.data,
+                    owner: pool_account.owner,
+                },
+            );
+        }
+
+        account_keys.sort();
+        account_keys.dedup();
+
+        for chunk in account_keys.chunks(100) {
+            let accounts = self.chain.get_multiple_accounts(chunk).await?;
+            for (account_key, account) in chunk.iter().zip(accounts) {
+                if let Some(account) = account {
+                    account_map.insert(
+                        *account_key,
+                        AccountData {
+                            data: account.data,
+                            owner: account.owner,
+                        },
+                    );
+                }
+            }
+        }
+
+        let epoch = self.chain.get_epoch().await?;
+
+        let mut pools = HashMap::new();
+        for pool_key in pool_keys {
+            if !account_map.contains_key(pool_key) {
+                continue;
+            }
+
+            match DarklakePool::from_account_map_with_network(
+                pool_key,
+                &account_map,
+                epoch,
+                self.network.clone(),
+            ) {
+                Ok(pool) => {
+                    pools.insert(*pool_key, pool);
+                }
+                Err(e) => log::warn!("Skipping pool {}: {}", pool_key, e),
+            }
+        }
+
+        Ok(pools)
+    }
+
+    /// Associated token account of the user for a mint of the loaded pool (or its LP mint) and its
+    /// balance, `None` if the account does not exist
+    async fn get_user_token_balance(
+        &self,
+        user: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<(Pubkey, Option<u64>)> {
+        let token_program = if *mint == self.darklake_amm.pool.token_mint_x {
+            self.darklake_amm.token_x_owner
+        } else if *mint == self.darklake_amm.pool.token_mint_y {
+            self.darklake_amm.token_y_owner
+        } else {
+            spl_token::ID
+        };
+        let token_account = DarklakeAmm::get_user_token_account(*user, *mint, token_program);
+
+        let balance = match self.chain.get_account(&token_account).await? {
+            Some(account) => Some(DarklakeAmm::parse_token_account_balance(
+                &account.data,
+                &account.owner,
+                &token_account,
+            )?),
+            None => None,
+        };
+
+        Ok((token_account, balance))
+    }
+
+    /// Rent of a new associated token account for the mint, including the account extensions a
+    /// Token-2022 mint requires
+    async fn get_token_account_rent(&self, mint: &Pubkey) -> Result<u64> {
+        let mint_account = self
+            .chain
+            .get_account(mint)
+            .await?
+            .with_context(|| format!("Mint {} not found", mint))?;
+        let account_len = get_token_account_len(&mint_account.data, &mint_account.owner)?;
+
+        self.chain
+            .get_minimum_balance_for_rent_exemption(account_len)
+            .await
+    }
+
+    /// Lamports of the account, zero if it does not exist
+    async fn get_sol_balance(&self, user: &Pubkey) -> Result<u64> {
+        Ok(self
+            .chain
+            .get_account(user)
+            .await?
+            .map_or(0, |account| account.lamports))
+    }
+
+    /// Settle an order, or cancel it and fail when it cannot be settled
+    async fn settle_or_cancel(
+        &mut self,
+        order_key: &Pubkey,
+        unwrap_wsol: bool,
+        min_out: u64,
+        salt: [u8; 8],
+        signer: &Keypair,
+        signatures: &mut Vec<Signature>,
+    ) -> Result<()> {
+        let settle_transaction = match self
+            .settle_tx(order_key, unwrap_wsol, min_out, salt, None)
+            .await
+        {
+            Ok(settle_transaction) => settle_transaction,
+            Err(e) => {
+                let cancel_transaction = self.cancel_tx(order_key, min_out, salt, None).await?;
+                signatures.push(self.sign_and_send(cancel_transaction, signer).await?);
+                bail!("Order could not be settled and was cancelled: {}", e);
+            }
+        };
+        signatures.push(self.sign_and_send(settle_transaction, signer).await?);
+
+        Ok(())
+    }
+
+    /// Sign a transaction with a single signer and send it, waiting for confirmation
+    async fn sign_and_send(
+        &self,
+        transaction: VersionedTransaction,
+        signer: &Keypair,
+    ) -> Result<Signature> {
+        let transaction = VersionedTransaction::try_new(transaction.message, &[signer])?;
+
+        let Some(rpc_client) = &self.rpc_client else {
+            bail!("Sending transactions requires an RPC endpoint, client or sender");
+        };
+
+        rpc_client
+            .send_and_confirm_transaction(&transaction)
+            .await
+            .map_err(|e| {
+                // the next transaction fetches the lookup tables again
+                if matches!(
+                    e.get_transaction_error(),
+                    Some(
+                        TransactionError::AddressLookupTableNotFound
+                            | TransactionError::InvalidAddressLookupTableIndex
+                            | TransactionError::InvalidAddressLookupTableData
+                            | TransactionError::InvalidAddressLookupTableOwner
+                    )
+                ) {
+                    self.lookup_table_cache.invalidate();
+                }
+                e.into()
+            })
+    }
+
+    /// Fail with a `SwapPreflightError` if the pool is halted or the trader has an open order
+    async fn check_swap_preflight(&self, trader: &Pubkey) -> Result<()> {
+        if !self.darklake_amm.is_active() {
+            return Err(SwapPreflightError::PoolHalted {
+                pool: self.darklake_amm.key(),
+            }
+            .into());
+        }
+
+        let order_key = self.darklake_amm.get_order_pubkey(trader)?;
+        let Some(order_account) = self.chain.get_account(&order_key).await? else {
+            return Ok(());
+        };
+
+        let order = Order::try_from_program_account_data(
+            &order_account.data,
+            &order_account.owner,
+            &self.network.program_id,
+        )?;
+        let current_slot = self.get_current_slot().await?;
+
+        Err(SwapPreflightError::OrderExists {
+            order: order_key,
+            deadline: order.deadline,
+            is_expired: current_slot > order.deadline,
+        }
+        .into())
+    }
+
+    /// Order of the trader in the loaded pool
+    async fn load_order(&self, order_owner: &Pubkey) -> Result<Order> {
+        let order_key = self.darklake_amm.get_order_pubkey(order_owner)?;
+        let order_account = self
+            .chain
+            .get_account(&order_key)
+            .await?
+            .with_context(|| format!("Order {} not found", order_key))?;
+
+        Ok(Order::try_from_program_account_data(
+            &order_account.data,
+            &order_account.owner,
+            &self.network.program_id,
+        )?)
+    }
+
+    /// The transfer of a cancelled or slashed order's input back to the trader
+    fn get_refund_transfer(&self, order: &Order) -> (Pubkey, bool, u64) {
+        let token_mint_in = if order.is_x_to_y {
+            self.darklake_amm.pool.token_mint_x
+        } else {
+            self.darklake_amm.pool.token_mint_y
+        };
+
+        (token_mint_in, false, order.actual_in)
     }
 
-    /// Get order data for a user
-    ///
-    /// This function does not require load_pool or update_accounts and is a standalone function
-    /// that can be called after new() is called.
-    ///
-    /// # Arguments
-    /// * `user` - The user's public key
-    /// * `commitment_level` - The commitment level for the RPC call
-    ///
-    /// # Returns
-    /// Returns the `Order` data for the user
-    pub async fn get_order(
+    /// Append the extra accounts transfer hook mints require for the instruction's transfers
+    async fn with_transfer_hook_accounts(
         &self,
+        mut instruction: Instruction,
         user: &Pubkey,
-        commitment_level: CommitmentLevel,
-    ) -> Result<Order> {
-        let order_key = self.darklake_amm.get_order_pubkey(user)?;
-
-        let order_data = self
-            .rpc_client
-            .get_account_with_commitment(
-                &order_key,
-                CommitmentConfig {
-                    commitment: commitment_level,
-                },
-            )
-            .await?
-            .value;
-        if order_data.is_none() {
-            return Err(anyhow::anyhow!("Order not found"));
-        }
+        transfers: &[(Pubkey, bool, u64)],
+    ) -> Result<Instruction> {
+        let transfer_hook_transfers = self
+            .darklake_amm
+            .get_transfer_hook_transfers(user, transfers)?;
 
-        let order_data = order_data.unwrap();
+        add_transfer_hook_accounts(
+            self.chain.as_ref(),
+            &mut instruction,
+            &transfer_hook_transfers,
+        )
+        .await?;
 
-        let order = self.darklake_amm.parse_order_data(&order_data.data)?;
+        Ok(instruction)
+    }
 
-        Ok(order)
+    async fn get_current_slot(&self) -> Result<u64> {
+        self.chain.get_slot().await
     }
 
-    /// Create a swap instruction
-    ///
-    /// # Arguments
-    /// * `swap_params` - The swap parameters
-    ///
-    /// # Returns
-    /// Returns a `Instruction` ready to be added to a transaction
-    pub async fn swap_ix(&self, swap_params: &SwapParamsIx) -> Result<Instruction> {
-        let swap_params = SwapParams {
-            source_mint: swap_params.source_mint,
-            destination_mint: swap_params.destination_mint,
-            token_transfer_authority: swap_params.token_transfer_authority,
-            amount_in: swap_params.amount_in,
-            swap_mode: swap_params.swap_mode,
-            min_out: swap_params.min_out,
-            salt: swap_params.salt,
-            label: self.label,
-        };
+    /// Plan a settle/cancel/slash instruction paid by the settler
+    async fn build_finalize_plan(
+        &self,
+        settler: &Pubkey,
+        finalize_instruction: Instruction,
+    ) -> Result<DarklakeTxPlan> {
+        let create_wsol_ata_ix =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                settler,
+                settler,
+                &native_mint::ID,
+                &spl_token::ID,
+            );
 
-        let swap_and_account_metas = self
-            .darklake_amm
-            .get_swap_and_account_metas(&swap_params)
-            .context("Failed to get swap instruction and account metadata")?;
+        self.build_plan(
+            settler,
+            Some(self.tx_defaults.compute_unit_limit),
+            vec![create_wsol_ata_ix],
+            finalize_instruction,
+            vec![],
+        )
+        .await
+    }
 
-        Ok(Instruction {
-            program_id: DARKLAKE_PROGRAM_ID,
-            accounts: swap_and_account_metas.account_metas,
-            data: swap_and_account_metas.data,
-        })
+    /// Plan an instruction with the compute budget and lookup tables of the SDK
+    async fn build_plan(
+        &self,
+        payer: &Pubkey,
+        compute_unit_limit: Option<u32>,
+        setup_instructions: Vec<Instruction>,
+        core_instruction: Instruction,
+        cleanup_instructions: Vec<Instruction>,
+    ) -> Result<DarklakeTxPlan> {
+        Ok(DarklakeTxPlan::new(
+            *payer,
+            self.get_compute_budget_instructions(compute_unit_limit),
+            setup_instructions,
+            core_instruction,
+            cleanup_instructions,
+            self.get_lookup_tables().await?,
+        ))
     }
 
-    /// Create a finalize instruction (settle or cancel)
-    ///
-    /// # Arguments
-    /// * `finalize_params` - The finalize parameters
+    /// Compile a plan into a transaction, see `compile_transaction`
+    async fn compile_plan(&self, plan: &DarklakeTxPlan) -> Result<VersionedTransaction> {
+        self.compile_transaction(&plan.payer, &plan.instructions())
+            .await
+    }
+
+    /// Compile the instructions into a transaction with the lookup tables and the latest blockhash,
+    /// or the durable nonce when one is set
     ///
-    /// # Returns
-    /// Returns a `Instruction` ready to be added to a transaction
-    pub async fn finalize_ix(&self, finalize_params: &FinalizeParamsIx) -> Result<Instruction> {
-        let finalize_params = FinalizeParams {
-            settle_signer: finalize_params.settle_signer,
-            order_owner: finalize_params.order_owner,
-            unwrap_wsol: finalize_params.unwrap_wsol,
-            min_out: finalize_params.min_out,
-            salt: finalize_params.salt,
-            output: finalize_params.output,
-            commitment: finalize_params.commitment,
-            deadline: finalize_params.deadline,
-            current_slot: finalize_params.current_slot,
-            label: self.label,
-            ref_code: self.ref_code,
+    /// A cached lookup table misses the addresses added since it was fetched, so the tables are
+    /// fetched again and the transaction recompiled once when compilation fails.
+    async fn compile_transaction(
+        &self,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+    ) -> Result<VersionedTransaction> {
+        let (instructions, recent_blockhash) = match &self.durable_nonce {
+            Some(durable_nonce) => {
+                let nonce_data = self.get_nonce_data(&durable_nonce.nonce_account).await?;
+                if nonce_data.authority != durable_nonce.nonce_authority {
+                    bail!(
+                        "Nonce account {} is controlled by {}, not {}",
+                        durable_nonce.nonce_account,
+                        nonce_data.authority,
+                        durable_nonce.nonce_authority
+                    );
+                }
+
+                // AdvanceNonceAccount has to be the first instruction
+                let mut nonce_instructions = vec![system_instruction::advance_nonce_account(
+                    &durable_nonce.nonce_account,
+                    &durable_nonce.nonce_authority,
+                )];
+                nonce_instructions.extend_from_slice(instructions);
+
+                (nonce_instructions, nonce_data.blockhash())
+            }
+            None => (
+                instructions.to_vec(),
+                self.chain.get_latest_blockhash().await?,
+            ),
         };
+        let lookup_tables = self.get_lookup_tables().await?;
 
-        let is_settle = finalize_params.min_out <= finalize_params.output;
-        let is_slash = finalize_params.current_slot > finalize_params.deadline;
+        match compile_v0_transaction(payer, &instructions, &lookup_tables, recent_blockhash) {
+            Ok(transaction) => Ok(transaction),
+            Err(e) => {
+                log::debug!("Refreshing lookup tables after failed compilation: {}", e);
+                self.lookup_table_cache.invalidate();
 
-        if is_slash {
-            let slash_and_account_metas =
-                self.darklake_amm
-                    .get_slash_and_account_metas(&SlashParams {
-                        settle_signer: finalize_params.settle_signer,
-                        order_owner: finalize_params.order_owner,
-                        deadline: finalize_params.deadline,
-                        current_slot: finalize_params.current_slot,
-                        label: finalize_params.label,
-                    })?;
-            return Ok(Instruction {
-                program_id: DARKLAKE_PROGRAM_ID,
-                accounts: slash_and_account_metas.account_metas,
-                data: slash_and_account_metas.data,
-            });
+                let lookup_tables = self.get_lookup_tables().await?;
+                compile_v0_transaction(payer, &instructions, &lookup_tables, recent_blockhash)
+            }
         }
+    }
 
-        let circuit_paths = if is_settle {
-            self.settle_paths.clone()
-        } else {
-            self.cancel_paths.clone()
-        };
+    /// Compute unit limit when given, and price when a priority fee is configured
+    fn get_compute_budget_instructions(&self, compute_unit_limit: Option<u32>) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        if let Some(compute_unit_limit) = compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+                compute_unit_limit,
+            ));
+        }
+        if let Some(compute_unit_price) = self.tx_defaults.compute_unit_price {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+                compute_unit_price,
+            ));
+        }
+
+        instructions
+    }
 
+    async fn generate_finalize_proof(
+        &self,
+        circuit_paths: &ProofCircuitPaths,
+        min_out: u64,
+        salt: [u8; 8],
+        output: u64,
+        commitment: [u8; 32],
+    ) -> Result<ProofParams> {
         let private_inputs = PrivateProofInputs {
-            min_out: finalize_params.min_out,
-            salt: u64::from_le_bytes(finalize_params.salt),
+            min_out,
+            salt: u64::from_le_bytes(salt),
         };
 
         let public_inputs = PublicProofInputs {
-            real_out: finalize_params.output,
-            commitment: from_32_byte_buffer(&finalize_params.commitment),
+            real_out: output,
+            commitment: from_32_byte_buffer(&commitment),
         };
 
         let (proof, _) = generate_proof(
@@ -888,166 +2945,437 @@ impl DarklakeSDK {
             .try_into()
             .map_err(|_| anyhow::anyhow!("Invalid public signals length"))?;
 
-        if is_settle {
-            let settle_params = SettleParams {
-                settle_signer: finalize_params.settle_signer,
-                order_owner: finalize_params.order_owner,
-                unwrap_wsol: finalize_params.unwrap_wsol,
-                min_out: finalize_params.min_out,
-                salt: finalize_params.salt,
-                output: finalize_params.output,
-                commitment: finalize_params.commitment,
-                deadline: finalize_params.deadline,
-                current_slot: finalize_params.current_slot,
-                ref_code: finalize_params.ref_code,
-                label: finalize_params.label,
-            };
-            let settle_and_account_metas = self.darklake_amm.get_settle_and_account_metas(
-                &settle_params,
-                &ProofParams {
-                    generated_proof: solana_proof,
-                    public_inputs: public_inputs_arr,
-                },
-            )?;
-
-            return Ok(Instruction {
-                program_id: DARKLAKE_PROGRAM_ID,
-                accounts: settle_and_account_metas.account_metas,
-                data: settle_and_account_metas.data,
-            });
-        }
+        Ok(ProofParams {
+            generated_proof: solana_proof,
+            public_inputs: public_inputs_arr,
+        })
+    }
 
-        let cancel_params = CancelParams {
-            settle_signer: finalize_params.settle_signer,
-            order_owner: finalize_params.order_owner,
-            min_out: finalize_params.min_out,
-            salt: finalize_params.salt,
-            output: finalize_params.output,
-            commitment: finalize_params.commitment,
-            deadline: finalize_params.deadline,
-            current_slot: finalize_params.current_slot,
-            label: finalize_params.label,
+    /// Helpers internal methods
+    /// Get the pool address for a token pair in the configured AmmConfig
+    fn get_pool_address(
+        &self,
+        token_mint_x: &Pubkey,
+        token_mint_y: &Pubkey,
+    ) -> (Pubkey, Pubkey, Pubkey) {
+        let (ordered_x, ordered_y) = if token_mint_x < token_mint_y {
+            (token_mint_x, token_mint_y)
+        } else {
+            (token_mint_y, token_mint_x)
         };
-        let cancel_and_account_metas = self.darklake_amm.get_cancel_and_account_metas(
-            &cancel_params,
-            &ProofParams {
-                generated_proof: solana_proof,
-                public_inputs: public_inputs_arr,
-            },
-        )?;
 
-        return Ok(Instruction {
-            program_id: DARKLAKE_PROGRAM_ID,
-            accounts: cancel_and_account_metas.account_metas,
-            data: cancel_and_account_metas.data,
-        });
+        let pool_key = crate::darklake_amm::DarklakeAmm::get_pool_address(
+            &self.network.program_id,
+            &self.amm_config,
+            ordered_x,
+            ordered_y,
+        );
+
+        (pool_key, ordered_x.clone(), ordered_y.clone())
     }
+}
 
-    /// Create an add liquidity instruction
-    ///
-    /// # Arguments
-    /// * `add_liquidity_params` - The add liquidity parameters
-    ///
-    /// # Returns
-    /// Returns a `Instruction` ready to be added to a transaction
-    pub async fn add_liquidity_ix(
-        &self,
-        add_liquidity_params: &AddLiquidityParamsIx,
-    ) -> Result<Instruction> {
-        let add_liquidity_params = AddLiquidityParams {
-            amount_lp: add_liquidity_params.amount_lp,
-            max_amount_x: add_liquidity_params.max_amount_x,
-            max_amount_y: add_liquidity_params.max_amount_y,
-            user: add_liquidity_params.user,
-            label: self.label,
-            ref_code: self.ref_code,
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        chain_reader::InMemoryChainReader,
+        constants::{
+            AMM_CONFIG_DISCRIMINATOR, DARKLAKE_PROGRAM_ID, MAINNET_LOOKUP, ORDER_DISCRIMINATOR,
+            ORDER_SEED, SLASH_DISCRIMINATOR, SWAP_DISCRIMINATOR,
+        },
+        instruction_decoder::{DarklakeInstruction, decode_compiled_instruction},
+        test_utils::{anchor_account, lookup_table_account, nonce_account, pool_accounts},
+    };
+    use solana_rpc_client::mock_sender::MockSender;
+    use solana_sdk::hash::Hash;
+
+    fn offline_chain_reader(
+        token_a: Pubkey,
+        token_b: Pubkey,
+    ) -> (Arc<InMemoryChainReader>, Pubkey, Pool) {
+        let (pool_key, pool, mut account_map) =
+            pool_accounts(token_a, token_b, 1_000_000_000, 1_000_000_000);
+        account_map.insert(MAINNET_LOOKUP, lookup_table_account(vec![]));
+
+        let chain_reader = Arc::new(InMemoryChainReader::new());
+        chain_reader.set_account_map(&account_map);
+
+        (chain_reader, pool_key, pool)
+    }
 
-        let add_liquidity_and_account_metas = self
-            .darklake_amm
-            .get_add_liquidity_and_account_metas(&add_liquidity_params)?;
+    /// Open x to y order of `trader` in `pool`
+    fn order_account(trader: Pubkey, pool: &Pool, deadline: u64) -> AccountData {
+        anchor_account(
+            &ORDER_DISCRIMINATOR,
+            &Order {
+                trader,
+                token_mint_x: pool.token_mint_x,
+                token_mint_y: pool.token_mint_y,
+                actual_in: 1_000_000,
+                exchange_in: 0,
+                actual_out: 0,
+                from_to_lock: 0,
+                d_in: 0,
+                d_out: 0,
+                deadline,
+                protocol_fee: 0,
+                wsol_deposit: 0,
+                c_min: [0; 32],
+                is_x_to_y: true,
+                bump: 0,
+                lp_fee: 0,
+                padding: [0; 3],
+            },
+        )
+    }
 
-        Ok(Instruction {
-            program_id: DARKLAKE_PROGRAM_ID,
-            accounts: add_liquidity_and_account_metas.account_metas,
-            data: add_liquidity_and_account_metas.data,
-        })
+    fn order_key(pool_key: &Pubkey, trader: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[ORDER_SEED, pool_key.as_ref(), trader.as_ref()],
+            &DARKLAKE_PROGRAM_ID,
+        )
+        .0
     }
 
-    /// Create a remove liquidity instruction
-    ///
-    /// # Arguments
-    /// * `remove_liquidity_params` - The remove liquidity parameters
-    ///
-    /// # Returns
-    /// Returns a `Instruction` ready to be added to a transaction
-    pub async fn remove_liquidity_ix(
-        &self,
-        remove_liquidity_params: &RemoveLiquidityParamsIx,
-    ) -> Result<Instruction> {
-        let remove_liquidity_params = RemoveLiquidityParams {
-            amount_lp: remove_liquidity_params.amount_lp,
-            min_amount_x: remove_liquidity_params.min_amount_x,
-            min_amount_y: remove_liquidity_params.min_amount_y,
-            user: remove_liquidity_params.user,
-            label: self.label,
-        };
+    #[test]
+    fn test_rpc_sender_uses_commitment_set_after_it() {
+        let sdk = DarklakeSDK::builder()
+            .rpc_sender(MockSender::new("succeeds".to_string()))
+            .commitment(CommitmentLevel::Finalized)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            sdk.rpc_client.unwrap().commitment(),
+            CommitmentConfig::finalized()
+        );
+    }
 
-        let remove_liquidity_and_account_metas = self
-            .darklake_amm
-            .get_remove_liquidity_and_account_metas(&remove_liquidity_params)?;
+    #[tokio::test]
+    async fn test_swap_tx_offline() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (chain_reader, pool_key, pool) = offline_chain_reader(token_a, token_b);
+        let blockhash = Hash::new_unique();
+        chain_reader.set_blockhash(blockhash);
+
+        let mut sdk = DarklakeSDK::builder()
+            .chain_reader(chain_reader.clone())
+            .build()
+            .unwrap();
+
+        let user = Pubkey::new_unique();
+        let (transaction, order_key, min_out, _) = sdk
+            .swap_tx(&token_a, &token_b, 1_000_000, 900_000, &user)
+            .await
+            .unwrap();
+
+        assert_eq!(sdk.darklake_amm.key(), pool_key);
+        assert_eq!(*transaction.message.recent_blockhash(), blockhash);
+        assert_eq!(transaction.message.static_account_keys()[0], user);
+        assert_eq!(order_key, sdk.darklake_amm.get_order_pubkey(&user).unwrap());
+        assert_eq!(min_out, 900_000);
+
+        // the next swap sees the open order
+        chain_reader.set_account_map(&AccountMap::from([(
+            order_key,
+            order_account(user, &pool, 100),
+        )]));
+        chain_reader.set_slot(50);
+
+        let err = sdk
+            .swap_tx(&token_a, &token_b, 1_000_000, 900_000, &user)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SwapPreflightError>(),
+            Some(&SwapPreflightError::OrderExists {
+                order: order_key,
+                deadline: 100,
+                is_expired: false,
+            })
+        );
+    }
 
-        Ok(Instruction {
-            program_id: DARKLAKE_PROGRAM_ID,
-            accounts: remove_liquidity_and_account_metas.account_metas,
-            data: remove_liquidity_and_account_metas.data,
-        })
+    #[tokio::test]
+    async fn test_swap_tx_fails_on_halted_pool() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (chain_reader, pool_key, pool) = offline_chain_reader(token_a, token_b);
+        chain_reader.set_account_map(&AccountMap::from([(
+            pool.amm_config,
+            anchor_account(
+                &AMM_CONFIG_DISCRIMINATOR,
+                &AmmConfig {
+                    trade_fee_rate: 3_000,
+                    halted: true,
+                    ..AmmConfig::default()
+                },
+            ),
+        )]));
+
+        let mut sdk = DarklakeSDK::builder()
+            .chain_reader(chain_reader)
+            .build()
+            .unwrap();
+
+        let err = sdk
+            .swap_tx(&token_a, &token_b, 1_000_000, 1, &Pubkey::new_unique())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SwapPreflightError>(),
+            Some(&SwapPreflightError::PoolHalted { pool: pool_key })
+        );
     }
 
-    /// Create an initialize pool instruction
-    ///
-    /// # Arguments
-    /// * `initialize_pool_params` - The initialize pool parameters
-    ///
-    /// # Returns
-    /// Returns a `Instruction` ready to be added to a transaction
-    pub async fn initialize_pool_ix(
-        &self,
-        initialize_pool_params: &InitializePoolParamsIx,
-    ) -> Result<Instruction> {
-        let initialize_pool_params = InitializePoolParams {
-            user: initialize_pool_params.user,
-            token_x: initialize_pool_params.token_x,
-            token_x_program: initialize_pool_params.token_x_program,
-            token_y: initialize_pool_params.token_y,
-            token_y_program: initialize_pool_params.token_y_program,
-            amount_x: initialize_pool_params.amount_x,
-            amount_y: initialize_pool_params.amount_y,
-            label: self.label,
-        };
+    #[tokio::test]
+    async fn test_swap_plan_slashes_expired_order() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (chain_reader, pool_key, pool) = offline_chain_reader(token_a, token_b);
+
+        let user = Pubkey::new_unique();
+        let order_key = order_key(&pool_key, &user);
+        chain_reader.set_account_map(&AccountMap::from([(
+            order_key,
+            order_account(user, &pool, 100),
+        )]));
+        chain_reader.set_slot(200);
+
+        let mut sdk = DarklakeSDK::builder()
+            .chain_reader(chain_reader)
+            .build()
+            .unwrap();
+
+        let err = sdk
+            .swap_plan(&token_a, &token_b, 1_000_000, 1, &user)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SwapPreflightError>(),
+            Some(&SwapPreflightError::OrderExists {
+                order: order_key,
+                deadline: 100,
+                is_expired: true,
+            })
+        );
+
+        sdk.set_slash_expired_orders(true);
+        let (plan, _, _, _) = sdk
+            .swap_plan(&token_a, &token_b, 1_000_000, 1, &user)
+            .await
+            .unwrap();
+
+        // the WSOL account receiving the slashed deposit, then the slash, then the swap
+        assert_eq!(plan.setup_instructions.len(), 2);
+        assert_eq!(
+            plan.setup_instructions[0].program_id,
+            spl_associated_token_account::ID
+        );
+        assert_eq!(plan.setup_instructions[1].program_id, DARKLAKE_PROGRAM_ID);
+        assert_eq!(plan.setup_instructions[1].data[..8], SLASH_DISCRIMINATOR);
+        assert_eq!(plan.core_instruction.data[..8], SWAP_DISCRIMINATOR);
+    }
 
-        let initialize_pool_and_account_metas = self
-            .darklake_amm
-            .get_initialize_pool_and_account_metas(&initialize_pool_params, self.is_devnet)?;
+    #[tokio::test]
+    async fn test_swap_plan_wraps_sol_in_setup() {
+        let token = Pubkey::new_unique();
+        let (chain_reader, _, _) = offline_chain_reader(native_mint::ID, token);
+
+        let mut sdk = DarklakeSDK::builder()
+            .chain_reader(chain_reader)
+            .tx_defaults(TxDefaults {
+                compute_unit_price: Some(1_000),
+                ..TxDefaults::default()
+            })
+            .build()
+            .unwrap();
+
+        let user = Pubkey::new_unique();
+        let (plan, _, _, _) = sdk
+            .swap_plan(&SOL_MINT, &token, 1_000_000, 1, &user)
+            .await
+            .unwrap();
+
+        assert_eq!(plan.payer, user);
+        assert_eq!(plan.signers, vec![user]);
+        assert_eq!(plan.compute_budget_instructions.len(), 2);
+        assert_eq!(plan.setup_instructions.len(), 3);
+        assert_eq!(plan.core_instruction.program_id, DARKLAKE_PROGRAM_ID);
+        assert!(plan.cleanup_instructions.is_empty());
+        assert_eq!(plan.lookup_tables[0].key, MAINNET_LOOKUP);
+        assert_eq!(plan.instructions().len(), 6);
+    }
 
-        Ok(Instruction {
-            program_id: DARKLAKE_PROGRAM_ID,
-            accounts: initialize_pool_and_account_metas.account_metas,
-            data: initialize_pool_and_account_metas.data,
-        })
+    #[tokio::test]
+    async fn test_swap_tx_with_durable_nonce() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (chain_reader, _, _) = offline_chain_reader(token_a, token_b);
+
+        let (nonce_account_key, nonce_authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        chain_reader.set_account_map(&AccountMap::from([(
+            nonce_account_key,
+            nonce_account(nonce_authority, Hash::new_unique()),
+        )]));
+
+        let durable_nonce = DurableNonceConfig {
+            nonce_account: nonce_account_key,
+            nonce_authority,
+        };
+        let mut sdk = DarklakeSDK::builder()
+            .chain_reader(chain_reader)
+            .durable_nonce(durable_nonce.clone())
+            .build()
+            .unwrap();
+        let nonce_data = sdk.get_nonce_data(&nonce_account_key).await.unwrap();
+
+        let user = Pubkey::new_unique();
+        let (transaction, _, _, _) = sdk
+            .swap_tx(&token_a, &token_b, 1_000_000, 1, &user)
+            .await
+            .unwrap();
+
+        let account_keys = transaction.message.static_account_keys();
+        let first_instruction = &transaction.message.instructions()[0];
+        assert_eq!(
+            account_keys[usize::from(first_instruction.program_id_index)],
+            solana_sdk::system_program::ID
+        );
+        assert!(account_keys.contains(&nonce_authority));
+        assert_eq!(
+            *transaction.message.recent_blockhash(),
+            nonce_data.blockhash()
+        );
+
+        sdk.set_durable_nonce(Some(DurableNonceConfig {
+            nonce_authority: Pubkey::new_unique(),
+            ..durable_nonce
+        }));
+        assert!(
+            sdk.swap_tx(&token_a, &token_b, 1_000_000, 1, &user)
+                .await
+                .is_err()
+        );
     }
 
-    /// Helpers internal methods
-    /// Get the pool address for a token pair
-    fn get_pool_address(token_mint_x: &Pubkey, token_mint_y: &Pubkey) -> (Pubkey, Pubkey, Pubkey) {
-        let (ordered_x, ordered_y) = if token_mint_x < token_mint_y {
-            (token_mint_x, token_mint_y)
-        } else {
-            (token_mint_y, token_mint_x)
+    /// The Darklake instruction of `transaction` decoded with its static keys
+    fn decode_darklake_instruction(transaction: &VersionedTransaction) -> DarklakeInstruction {
+        let account_keys = transaction.message.static_account_keys();
+        let instruction = transaction
+            .message
+            .instructions()
+            .iter()
+            .find(|ix| account_keys[usize::from(ix.program_id_index)] == DARKLAKE_PROGRAM_ID)
+            .unwrap();
+
+        decode_compiled_instruction(instruction, account_keys).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_liquidity_tx_offline() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (chain_reader, pool_key, pool) = offline_chain_reader(token_a, token_b);
+
+        let mut sdk = DarklakeSDK::builder()
+            .chain_reader(chain_reader)
+            .build()
+            .unwrap();
+
+        // the max amounts follow the mints when they are passed unsorted
+        let user = Pubkey::new_unique();
+        let transaction = sdk
+            .add_liquidity_tx(
+                &pool.token_mint_y,
+                &pool.token_mint_x,
+                2_000,
+                1_000,
+                500,
+                &user,
+            )
+            .await
+            .unwrap();
+
+        let DarklakeInstruction::AddLiquidity { params, accounts } =
+            decode_darklake_instruction(&transaction)
+        else {
+            panic!("expected an add liquidity instruction");
         };
+        assert_eq!(params.amount_lp, 500);
+        assert_eq!(params.max_amount_x, 1_000);
+        assert_eq!(params.max_amount_y, 2_000);
+        assert_eq!(accounts.user, user);
+        assert_eq!(accounts.pool, pool_key);
+        assert_eq!(accounts.token_mint_x, pool.token_mint_x);
+        assert_eq!(accounts.token_mint_y, pool.token_mint_y);
+    }
 
-        let pool_key = crate::darklake_amm::DarklakeAmm::get_pool_address(ordered_x, ordered_y);
+    #[tokio::test]
+    async fn test_remove_liquidity_tx_offline() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (chain_reader, pool_key, pool) = offline_chain_reader(token_a, token_b);
 
-        (pool_key, ordered_x.clone(), ordered_y.clone())
+        let mut sdk = DarklakeSDK::builder()
+            .chain_reader(chain_reader)
+            .build()
+            .unwrap();
+
+        let user = Pubkey::new_unique();
+        let transaction = sdk
+            .remove_liquidity_tx(&pool.token_mint_x, &pool.token_mint_y, 100, 200, 500, &user)
+            .await
+            .unwrap();
+
+        // both user token accounts are created before the withdrawal
+        let account_keys = transaction.message.static_account_keys();
+        let ata_instructions = transaction
+            .message
+            .instructions()
+            .iter()
+            .filter(|ix| {
+                account_keys[usize::from(ix.program_id_index)] == spl_associated_token_account::ID
+            })
+            .count();
+        assert_eq!(ata_instructions, 2);
+
+        let DarklakeInstruction::RemoveLiquidity { params, accounts } =
+            decode_darklake_instruction(&transaction)
+        else {
+            panic!("expected a remove liquidity instruction");
+        };
+        assert_eq!(params.amount_lp, 500);
+        assert_eq!(params.min_amount_x, 100);
+        assert_eq!(params.min_amount_y, 200);
+        assert_eq!(accounts.user, user);
+        assert_eq!(accounts.pool, pool_key);
+    }
+
+    #[tokio::test]
+    async fn test_slash_tx_offline() {
+        let (token_a, token_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let (chain_reader, pool_key, pool) = offline_chain_reader(token_a, token_b);
+
+        let trader = Pubkey::new_unique();
+        let order_key = order_key(&pool_key, &trader);
+        chain_reader.set_account_map(&AccountMap::from([(
+            order_key,
+            order_account(trader, &pool, 100),
+        )]));
+        chain_reader.set_slot(200);
+
+        let mut sdk = DarklakeSDK::builder()
+            .chain_reader(chain_reader)
+            .build()
+            .unwrap();
+
+        let caller = Pubkey::new_unique();
+        let transaction = sdk.slash_tx(&order_key, Some(&caller)).await.unwrap();
+
+        assert_eq!(transaction.message.static_account_keys()[0], caller);
+        let DarklakeInstruction::Slash { accounts, .. } = decode_darklake_instruction(&transaction)
+        else {
+            panic!("expected a slash instruction");
+        };
+        assert_eq!(accounts.caller, caller);
+        assert_eq!(accounts.order_owner, trader);
+        assert_eq!(accounts.pool, pool_key);
     }
 }
@@ -0,0 +1,243 @@
+// Serde helpers used by the `serde` feature: pubkeys as base58 strings and byte arrays as hex
+
+pub(crate) mod pubkey {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(pubkey)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let pubkey = String::deserialize(deserializer)?;
+        Pubkey::from_str(&pubkey).map_err(Error::custom)
+    }
+}
+
+pub(crate) mod option_pubkey {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(
+        pubkey: &Option<Pubkey>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match pubkey {
+            Some(pubkey) => serializer.serialize_some(&pubkey.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Pubkey>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|pubkey| Pubkey::from_str(&pubkey).map_err(Error::custom))
+            .transpose()
+    }
+}
+
+pub(crate) mod hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        bytes: &[u8; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[u8; N], D::Error> {
+        let hex_string = String::deserialize(deserializer)?;
+        from_hex(&hex_string).map_err(Error::custom)
+    }
+
+    pub(crate) fn from_hex<const N: usize>(hex_string: &str) -> Result<[u8; N], String> {
+        let bytes = hex::decode(hex_string).map_err(|e| e.to_string())?;
+        bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| format!("Expected {} bytes, got {}", N, bytes.len()))
+    }
+}
+
+pub(crate) mod option_hex_bytes {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        bytes: &Option<[u8; N]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&hex::encode(bytes)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<Option<[u8; N]>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|hex_string| super::hex_bytes::from_hex(&hex_string).map_err(Error::custom))
+            .transpose()
+    }
+}
+
+pub(crate) mod hex_bytes_array {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error, ser::SerializeSeq};
+
+    pub fn serialize<S: Serializer, const N: usize, const M: usize>(
+        bytes_array: &[[u8; N]; M],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(M))?;
+        for bytes in bytes_array {
+            seq.serialize_element(&hex::encode(bytes))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize, const M: usize>(
+        deserializer: D,
+    ) -> Result<[[u8; N]; M], D::Error> {
+        let bytes_vec = Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|hex_string| super::hex_bytes::from_hex(hex_string))
+            .collect::<Result<Vec<[u8; N]>, String>>()
+            .map_err(Error::custom)?;
+
+        bytes_vec.try_into().map_err(|bytes_vec: Vec<[u8; N]>| {
+            Error::custom(format!("Expected {} items, got {}", M, bytes_vec.len()))
+        })
+    }
+}
+
+pub(crate) mod hex_vec {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex_string = String::deserialize(deserializer)?;
+        hex::decode(hex_string).map_err(Error::custom)
+    }
+}
+
+pub(crate) mod hex_bytes_vec {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error, ser::SerializeSeq};
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        bytes_vec: &[[u8; N]],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(bytes_vec.len()))?;
+        for bytes in bytes_vec {
+            seq.serialize_element(&hex::encode(bytes))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<Vec<[u8; N]>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|hex_string| super::hex_bytes::from_hex(hex_string))
+            .collect::<Result<Vec<[u8; N]>, String>>()
+            .map_err(Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AccountData, Order, SwapParamsIx};
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_pubkeys_and_bytes_serialize_as_strings() {
+        let params = SwapParamsIx {
+            source_mint: Pubkey::new_unique(),
+            destination_mint: Pubkey::new_unique(),
+            token_transfer_authority: Pubkey::new_unique(),
+            amount_in: 1_000,
+            swap_mode: crate::SwapMode::ExactIn,
+            min_out: 1,
+            salt: [1, 2, 3, 4, 5, 6, 7, 8],
+        };
+
+        let json = serde_json::to_value(&params).unwrap();
+        assert_eq!(json["source_mint"], params.source_mint.to_string());
+        assert_eq!(json["salt"], "0102030405060708");
+
+        let decoded: SwapParamsIx = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.source_mint, params.source_mint);
+        assert_eq!(decoded.salt, params.salt);
+    }
+
+    #[test]
+    fn test_order_round_trip() {
+        let order = Order {
+            trader: Pubkey::new_unique(),
+            token_mint_x: Pubkey::new_unique(),
+            token_mint_y: Pubkey::new_unique(),
+            actual_in: 1,
+            exchange_in: 2,
+            actual_out: 3,
+            from_to_lock: 4,
+            d_in: 5,
+            d_out: 6,
+            deadline: 7,
+            protocol_fee: 8,
+            wsol_deposit: 9,
+            c_min: [0xab; 32],
+            is_x_to_y: true,
+            bump: 255,
+            lp_fee: 10,
+            padding: [0; 3],
+        };
+
+        let json = serde_json::to_value(&order).unwrap();
+        assert_eq!(json["c_min"], "ab".repeat(32));
+
+        let decoded: Order = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.trader, order.trader);
+        assert_eq!(decoded.c_min, order.c_min);
+        assert_eq!(decoded.d_out, 6);
+    }
+
+    #[test]
+    fn test_account_data_round_trip() {
+        let account = AccountData {
+            data: vec![0xde, 0xad, 0xbe, 0xef],
+            owner: Pubkey::new_unique(),
+        };
+
+        let json = serde_json::to_value(&account).unwrap();
+        assert_eq!(json["data"], "deadbeef");
+        assert_eq!(json["owner"], account.owner.to_string());
+
+        let decoded: AccountData = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.data, account.data);
+        assert_eq!(decoded.owner, account.owner);
+    }
+
+    #[test]
+    fn test_invalid_hex_length_is_rejected() {
+        let json = serde_json::json!({
+            "source_mint": Pubkey::new_unique().to_string(),
+            "destination_mint": Pubkey::new_unique().to_string(),
+            "token_transfer_authority": Pubkey::new_unique().to_string(),
+            "amount_in": 1,
+            "swap_mode": "ExactIn",
+            "min_out": 1,
+            "salt": "0102",
+        });
+
+        assert!(serde_json::from_value::<SwapParamsIx>(json).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+// Synthetic account builders shared by the unit tests
+
+use anchor_lang::AnchorSerialize;
+use solana_sdk::{
+    address_lookup_table::{
+        self,
+        state::{AddressLookupTable, LookupTableMeta},
+    },
+    hash::Hash,
+    nonce::state::{Data as NonceData, DurableNonce, State, Versions},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_pod::primitives::{PodU16, PodU64};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_token::state::{Account as SplTokenAccount, AccountState, Mint};
+use spl_token_2022::extension::{
+    BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    transfer_fee::{TransferFee, TransferFeeConfig},
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use std::borrow::Cow;
+
+use crate::{
+    amm::{AccountData, AccountMap},
+    constants::{AMM_CONFIG, AMM_CONFIG_DISCRIMINATOR, DARKLAKE_PROGRAM_ID, POOL_DISCRIMINATOR},
+    darklake_amm::{AmmConfig, DarklakeAmm, Pool},
+};
+
+pub(crate) fn anchor_account<T: AnchorSerialize>(
+    discriminator: &[u8; 8],
+    account: &T,
+) -> AccountData {
+    let mut data = discriminator.to_vec();
+    account.serialize(&mut data).unwrap();
+    AccountData {
+        data,
+        owner: DARKLAKE_PROGRAM_ID,
+    }
+}
+
+pub(crate) fn lookup_table_account(addresses: Vec<Pubkey>) -> AccountData {
+    AccountData {
+        data: AddressLookupTable {
+            meta: LookupTableMeta::default(),
+            addresses: Cow::Owned(addresses),
+        }
+        .serialize_for_tests()
+        .unwrap(),
+        owner: address_lookup_table::program::id(),
+    }
+}
+
+pub(crate) fn nonce_account(authority: Pubkey, blockhash: Hash) -> AccountData {
+    AccountData {
+        data: bincode::serialize(&Versions::new(State::Initialized(NonceData::new(
+            authority,
+            DurableNonce::from_blockhash(&blockhash),
+            5_000,
+        ))))
+        .unwrap(),
+        owner: system_program::ID,
+    }
+}
+
+pub(crate) fn token_account(mint: Pubkey, amount: u64) -> AccountData {
+    let mut data = vec![0u8; SplTokenAccount::LEN];
+    SplTokenAccount::pack(
+        SplTokenAccount {
+            mint,
+            owner: Pubkey::new_unique(),
+            amount,
+            state: AccountState::Initialized,
+            ..SplTokenAccount::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    AccountData {
+        data,
+        owner: spl_token::ID,
+    }
+}
+
+pub(crate) fn mint_account() -> AccountData {
+    let mut data = vec![0u8; Mint::LEN];
+    Mint::pack(
+        Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Mint::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    AccountData {
+        data,
+        owner: spl_token::ID,
+    }
+}
+
+/// Token-2022 mint with the given extensions, initialized by `init`
+pub(crate) fn token_2022_mint_account(
+    extension_types: &[ExtensionType],
+    init: impl FnOnce(&mut StateWithExtensionsMut<spl_token_2022::state::Mint>),
+) -> AccountData {
+    let space =
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extension_types)
+            .unwrap();
+    let mut data = vec![0u8; space];
+    let mut state =
+        StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+            .unwrap();
+    init(&mut state);
+    state.base = spl_token_2022::state::Mint {
+        decimals: 6,
+        is_initialized: true,
+        ..spl_token_2022::state::Mint::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    AccountData {
+        data,
+        owner: spl_token_2022::ID,
+    }
+}
+
+/// Transfer fee config charging `older_basis_points` until `newer_epoch`, then
+/// `newer_basis_points`, without a maximum fee
+pub(crate) fn transfer_fee_config(
+    older_basis_points: u16,
+    newer_basis_points: u16,
+    newer_epoch: u64,
+) -> TransferFeeConfig {
+    TransferFeeConfig {
+        older_transfer_fee: TransferFee {
+            epoch: PodU64::from(0),
+            maximum_fee: PodU64::from(u64::MAX),
+            transfer_fee_basis_points: PodU16::from(older_basis_points),
+        },
+        newer_transfer_fee: TransferFee {
+            epoch: PodU64::from(newer_epoch),
+            maximum_fee: PodU64::from(u64::MAX),
+            transfer_fee_basis_points: PodU16::from(newer_basis_points),
+        },
+        ..TransferFeeConfig::default()
+    }
+}
+
+/// Token-2022 mint with a transfer fee config
+pub(crate) fn transfer_fee_mint_account(transfer_fee_config: TransferFeeConfig) -> AccountData {
+    token_2022_mint_account(&[ExtensionType::TransferFeeConfig], |state| {
+        *state.init_extension::<TransferFeeConfig>(true).unwrap() = transfer_fee_config;
+    })
+}
+
+/// Token-2022 token account without extensions
+pub(crate) fn token_2022_account(mint: Pubkey, amount: u64) -> AccountData {
+    AccountData {
+        owner: spl_token_2022::ID,
+        ..token_account(mint, amount)
+    }
+}
+
+/// Transfer hook validation account listing fixed extra accounts for `Execute`
+pub(crate) fn extra_account_metas_account(
+    transfer_hook_program_id: Pubkey,
+    extra_account_metas: &[ExtraAccountMeta],
+) -> AccountData {
+    let mut data = vec![0; ExtraAccountMetaList::size_of(extra_account_metas.len()).unwrap()];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, extra_account_metas).unwrap();
+
+    AccountData {
+        data,
+        owner: transfer_hook_program_id,
+    }
+}
+
+/// Pool, AmmConfig, reserve and mint accounts for a 0.3% fee pool at its PDA
+///
+/// `reserve_a`/`reserve_b` are the reserves of `token_mint_a`/`token_mint_b`, which are sorted
+/// into x and y like the program does.
+pub(crate) fn pool_accounts(
+    token_mint_a: Pubkey,
+    token_mint_b: Pubkey,
+    reserve_a: u64,
+    reserve_b: u64,
+) -> (Pubkey, Pool, AccountMap) {
+    let (token_mint_x, token_mint_y, reserve_x, reserve_y) = if token_mint_a < token_mint_b {
+        (token_mint_a, token_mint_b, reserve_a, reserve_b)
+    } else {
+        (token_mint_b, token_mint_a, reserve_b, reserve_a)
+    };
+
+    let pool_key = DarklakeAmm::get_pool_address(
+        &DARKLAKE_PROGRAM_ID,
+        &AMM_CONFIG,
+        &token_mint_x,
+        &token_mint_y,
+    );
+    let pool = Pool {
+        amm_config: *AMM_CONFIG,
+        token_mint_x,
+        token_mint_y,
+        reserve_x: Pubkey::new_unique(),
+        reserve_y: Pubkey::new_unique(),
+        token_lp_supply: 1_000_000,
+        ..Pool::default()
+    };
+    let amm_config = AmmConfig {
+        trade_fee_rate: 3_000,
+        protocol_fee_rate: 100_000,
+        ratio_change_tolerance_rate: 10_000,
+        ..AmmConfig::default()
+    };
+
+    let mut account_map = AccountMap::new();
+    account_map.insert(pool_key, anchor_account(&POOL_DISCRIMINATOR, &pool));
+    account_map.insert(
+        pool.amm_config,
+        anchor_account(&AMM_CONFIG_DISCRIMINATOR, &amm_config),
+    );
+    account_map.insert(pool.reserve_x, token_account(token_mint_x, reserve_x));
+    account_map.insert(pool.reserve_y, token_account(token_mint_y, reserve_y));
+    account_map.insert(token_mint_x, mint_account());
+    account_map.insert(token_mint_y, mint_account());
+
+    (pool_key, pool, account_map)
+}
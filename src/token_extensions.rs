@@ -0,0 +1,371 @@
+use anyhow::{Result, bail};
+use solana_sdk::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        default_account_state::DefaultAccountState, permanent_delegate::PermanentDelegate,
+        transfer_fee::TransferFeeConfig, transfer_hook::TransferHook,
+    },
+    state::{Account, AccountState, Mint},
+};
+use spl_transfer_hook_interface::offchain::add_extra_account_metas_for_execute;
+
+use crate::chain_reader::ChainReader;
+
+/// Token-2022 mint extensions that change how tokens move in and out of a pool
+///
+/// Non-transferable mints are rejected when a pool is loaded. The other extensions are supported
+/// but flagged here: transfer hook accounts are added to instructions automatically, the rest may
+/// make transfers fail (frozen default account state) or let a third party move funds (permanent
+/// delegate) and should be surfaced to users.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MintExtensions {
+    pub transfer_fee: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::option_pubkey"))]
+    pub transfer_hook_program_id: Option<Pubkey>,
+    pub non_transferable: bool,
+    pub confidential_transfer: bool,
+    pub default_account_state_frozen: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::option_pubkey"))]
+    pub permanent_delegate: Option<Pubkey>,
+}
+
+impl MintExtensions {
+    /// Read the extensions of a mint account, mints owned by the legacy token program have none
+    pub fn from_mint_account_data(mint_account_data: &[u8], mint_owner: &Pubkey) -> Result<Self> {
+        let mut mint_extensions = MintExtensions::default();
+
+        if *mint_owner != spl_token_2022::ID {
+            return Ok(mint_extensions);
+        }
+
+        let mint = StateWithExtensions::<Mint>::unpack(mint_account_data)?;
+
+        for extension_type in mint.get_extension_types()? {
+            match extension_type {
+                ExtensionType::TransferFeeConfig => mint_extensions.transfer_fee = true,
+                ExtensionType::TransferHook => {
+                    mint_extensions.transfer_hook_program_id =
+                        mint.get_extension::<TransferHook>()?.program_id.into();
+                }
+                ExtensionType::NonTransferable => mint_extensions.non_transferable = true,
+                ExtensionType::ConfidentialTransferMint => {
+                    mint_extensions.confidential_transfer = true
+                }
+                ExtensionType::DefaultAccountState => {
+                    mint_extensions.default_account_state_frozen =
+                        mint.get_extension::<DefaultAccountState>()?.state
+                            == AccountState::Frozen as u8;
+                }
+                ExtensionType::PermanentDelegate => {
+                    mint_extensions.permanent_delegate =
+                        mint.get_extension::<PermanentDelegate>()?.delegate.into();
+                }
+                _ => {}
+            }
+        }
+
+        Ok(mint_extensions)
+    }
+
+    /// Fail for extensions that make a mint untradeable
+    pub(crate) fn check_supported(&self, mint: &Pubkey) -> Result<()> {
+        if self.non_transferable {
+            bail!("Mint {} is non-transferable and cannot be traded", mint);
+        }
+
+        Ok(())
+    }
+
+    /// Human readable notes for supported extensions users should know about
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if self.default_account_state_frozen {
+            warnings.push(
+                "New token accounts are frozen by default and must be thawed before receiving tokens"
+                    .to_string(),
+            );
+        }
+        if let Some(permanent_delegate) = self.permanent_delegate {
+            warnings.push(format!(
+                "Permanent delegate {} can transfer or burn tokens from any account",
+                permanent_delegate
+            ));
+        }
+        if self.confidential_transfer {
+            warnings.push(
+                "Confidential balances are not visible to the pool, only public balances can be traded"
+                    .to_string(),
+            );
+        }
+        if let Some(transfer_hook_program_id) = self.transfer_hook_program_id {
+            warnings.push(format!(
+                "Transfers invoke the transfer hook program {}",
+                transfer_hook_program_id
+            ));
+        }
+
+        warnings
+    }
+}
+
+/// Size of a new associated token account for a mint
+///
+/// Token-2022 accounts carry the account extensions the mint requires plus `ImmutableOwner`,
+/// which the associated token program always initializes.
+pub(crate) fn get_token_account_len(
+    mint_account_data: &[u8],
+    mint_owner: &Pubkey,
+) -> Result<usize> {
+    if *mint_owner != spl_token_2022::ID {
+        return Ok(spl_token::state::Account::LEN);
+    }
+
+    let mint = StateWithExtensions::<Mint>::unpack(mint_account_data)?;
+
+    let mut account_extensions =
+        ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?);
+    if !account_extensions.contains(&ExtensionType::ImmutableOwner) {
+        account_extensions.push(ExtensionType::ImmutableOwner);
+    }
+
+    Ok(ExtensionType::try_calculate_account_len::<Account>(
+        &account_extensions,
+    )?)
+}
+
+/// Read the transfer fee config of a mint, `None` for legacy token mints and Token-2022 mints
+/// without the extension
+pub(crate) fn get_transfer_fee_config(
+    mint_account_data: &[u8],
+    mint_owner: &Pubkey,
+) -> Result<Option<TransferFeeConfig>> {
+    if *mint_owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let mint = StateWithExtensions::<Mint>::unpack(mint_account_data)?;
+
+    if !mint
+        .get_extension_types()?
+        .contains(&ExtensionType::TransferFeeConfig)
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(*mint.get_extension::<TransferFeeConfig>()?))
+}
+
+/// A token transfer made by a Darklake instruction
+#[derive(Debug, Clone)]
+pub(crate) struct TokenTransfer {
+    pub mint: Pubkey,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+/// Append the transfer hook program, its validation account and the extra accounts it requires for
+/// every transfer of a transfer hook mint, so the program can forward them when transferring
+pub(crate) async fn add_transfer_hook_accounts(
+    chain: &dyn ChainReader,
+    instruction: &mut Instruction,
+    transfers: &[(TokenTransfer, &MintExtensions)],
+) -> Result<()> {
+    for (transfer, mint_extensions) in transfers {
+        let Some(transfer_hook_program_id) = mint_extensions.transfer_hook_program_id else {
+            continue;
+        };
+
+        add_extra_account_metas_for_execute(
+            instruction,
+            &transfer_hook_program_id,
+            &transfer.source,
+            &transfer.mint,
+            &transfer.destination,
+            &transfer.authority,
+            transfer.amount,
+            |address| async move {
+                chain
+                    .get_account(&address)
+                    .await
+                    .map(|account| account.map(|account| account.data))
+                    .map_err(|e| e.into())
+            },
+        )
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to resolve transfer hook accounts for mint {}: {}",
+                transfer.mint,
+                e
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        amm::AccountMap,
+        chain_reader::InMemoryChainReader,
+        test_utils::{
+            extra_account_metas_account, token_2022_mint_account, transfer_fee_config,
+            transfer_fee_mint_account,
+        },
+    };
+    use solana_sdk::instruction::AccountMeta;
+    use spl_pod::optional_keys::OptionalNonZeroPubkey;
+    use spl_tlv_account_resolution::account::ExtraAccountMeta;
+    use spl_token_2022::extension::{
+        BaseStateWithExtensionsMut, non_transferable::NonTransferable,
+    };
+    use spl_transfer_hook_interface::get_extra_account_metas_address;
+
+    #[test]
+    fn test_reads_transfer_hook_and_permanent_delegate() {
+        let transfer_hook_program_id = Pubkey::new_unique();
+        let permanent_delegate = Pubkey::new_unique();
+
+        let mint_account = token_2022_mint_account(
+            &[
+                ExtensionType::TransferHook,
+                ExtensionType::PermanentDelegate,
+            ],
+            |state| {
+                state
+                    .init_extension::<TransferHook>(true)
+                    .unwrap()
+                    .program_id =
+                    OptionalNonZeroPubkey::try_from(Some(transfer_hook_program_id)).unwrap();
+                state
+                    .init_extension::<PermanentDelegate>(true)
+                    .unwrap()
+                    .delegate = OptionalNonZeroPubkey::try_from(Some(permanent_delegate)).unwrap();
+            },
+        );
+
+        let mint_extensions =
+            MintExtensions::from_mint_account_data(&mint_account.data, &mint_account.owner)
+                .unwrap();
+        assert_eq!(
+            mint_extensions.transfer_hook_program_id,
+            Some(transfer_hook_program_id)
+        );
+        assert_eq!(mint_extensions.permanent_delegate, Some(permanent_delegate));
+        assert!(
+            mint_extensions
+                .check_supported(&Pubkey::new_unique())
+                .is_ok()
+        );
+        assert_eq!(mint_extensions.warnings().len(), 2);
+    }
+
+    #[test]
+    fn test_rejects_non_transferable_mint() {
+        let mint_account = token_2022_mint_account(&[ExtensionType::NonTransferable], |state| {
+            state.init_extension::<NonTransferable>(true).unwrap();
+        });
+
+        let mint_extensions =
+            MintExtensions::from_mint_account_data(&mint_account.data, &mint_account.owner)
+                .unwrap();
+        assert!(mint_extensions.non_transferable);
+        assert!(
+            mint_extensions
+                .check_supported(&Pubkey::new_unique())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_token_account_len_includes_required_extensions() {
+        assert_eq!(
+            get_token_account_len(&[], &spl_token::ID).unwrap(),
+            spl_token::state::Account::LEN
+        );
+
+        let mint_account = transfer_fee_mint_account(transfer_fee_config(100, 100, 0));
+        assert_eq!(
+            get_token_account_len(&mint_account.data, &mint_account.owner).unwrap(),
+            ExtensionType::try_calculate_account_len::<Account>(&[
+                ExtensionType::TransferFeeAmount,
+                ExtensionType::ImmutableOwner,
+            ])
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_adds_validation_account_and_extra_metas() {
+        let transfer_hook_program_id = Pubkey::new_unique();
+        let extra_account = Pubkey::new_unique();
+        let transfer = TokenTransfer {
+            mint: Pubkey::new_unique(),
+            source: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            amount: 1_000,
+        };
+        let validation_account =
+            get_extra_account_metas_address(&transfer.mint, &transfer_hook_program_id);
+
+        let chain_reader = InMemoryChainReader::new();
+        chain_reader.set_account_map(&AccountMap::from([(
+            validation_account,
+            extra_account_metas_account(
+                transfer_hook_program_id,
+                &[ExtraAccountMeta::new_with_pubkey(&extra_account, false, true).unwrap()],
+            ),
+        )]));
+
+        let mut instruction = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![
+                AccountMeta::new(transfer.source, false),
+                AccountMeta::new_readonly(transfer.mint, false),
+                AccountMeta::new(transfer.destination, false),
+                AccountMeta::new_readonly(transfer.authority, true),
+            ],
+            data: vec![],
+        };
+        let mint_extensions = MintExtensions {
+            transfer_hook_program_id: Some(transfer_hook_program_id),
+            ..MintExtensions::default()
+        };
+
+        add_transfer_hook_accounts(
+            &chain_reader,
+            &mut instruction,
+            &[
+                (transfer.clone(), &mint_extensions),
+                (transfer, &MintExtensions::default()),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            instruction.accounts[4..],
+            [
+                AccountMeta::new(extra_account, false),
+                AccountMeta::new_readonly(transfer_hook_program_id, false),
+                AccountMeta::new_readonly(validation_account, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_legacy_token_mint_has_no_extensions() {
+        assert_eq!(
+            MintExtensions::from_mint_account_data(&[], &spl_token::ID).unwrap(),
+            MintExtensions::default()
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount, hash::Hash, instruction::Instruction,
+    pubkey::Pubkey, transaction::VersionedTransaction,
+};
+
+use crate::utils::compile_v0_transaction;
+
+/// The instructions of a Darklake operation before they are compiled into a transaction
+///
+/// Returned by the `_plan` functions so integrators can merge a Darklake operation with their own
+/// instructions and compile a single transaction. Keep the order setup, core, cleanup and use a
+/// single set of compute budget instructions for the whole transaction.
+#[derive(Debug, Clone)]
+pub struct DarklakeTxPlan {
+    /// Fee payer the `_tx` functions compile the transaction for
+    pub payer: Pubkey,
+    /// Compute unit limit and price from the SDK's `TxDefaults`
+    pub compute_budget_instructions: Vec<Instruction>,
+    /// Instructions that must run before `core_instruction`: WSOL wrapping, token account creation
+    /// and the slash of an expired order
+    pub setup_instructions: Vec<Instruction>,
+    /// The Darklake program instruction
+    pub core_instruction: Instruction,
+    /// Instructions that must run after `core_instruction`, e.g. closing the WSOL account
+    pub cleanup_instructions: Vec<Instruction>,
+    /// The network's lookup table followed by the SDK's extra lookup tables
+    pub lookup_tables: Vec<AddressLookupTableAccount>,
+    /// Every account that has to sign, payer first
+    pub signers: Vec<Pubkey>,
+}
+
+impl DarklakeTxPlan {
+    pub(crate) fn new(
+        payer: Pubkey,
+        compute_budget_instructions: Vec<Instruction>,
+        setup_instructions: Vec<Instruction>,
+        core_instruction: Instruction,
+        cleanup_instructions: Vec<Instruction>,
+        lookup_tables: Vec<AddressLookupTableAccount>,
+    ) -> Self {
+        let mut plan = Self {
+            payer,
+            compute_budget_instructions,
+            setup_instructions,
+            core_instruction,
+            cleanup_instructions,
+            lookup_tables,
+            signers: vec![payer],
+        };
+
+        for account_meta in plan
+            .instructions()
+            .iter()
+            .flat_map(|instruction| &instruction.accounts)
+        {
+            if account_meta.is_signer && !plan.signers.contains(&account_meta.pubkey) {
+                plan.signers.push(account_meta.pubkey);
+            }
+        }
+
+        plan
+    }
+
+    /// Every instruction in execution order: compute budget, setup, core, cleanup
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = self.compute_budget_instructions.clone();
+        instructions.extend(self.setup_instructions.iter().cloned());
+        instructions.push(self.core_instruction.clone());
+        instructions.extend(self.cleanup_instructions.iter().cloned());
+        instructions
+    }
+
+    /// Compile the plan as is into an unsigned transaction
+    ///
+    /// # Returns
+    /// Returns an error if the transaction does not fit into a packet once signed
+    pub fn compile(&self, recent_blockhash: Hash) -> Result<VersionedTransaction> {
+        compile_v0_transaction(
+            &self.payer,
+            &self.instructions(),
+            &self.lookup_tables,
+            recent_blockhash,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::AccountMeta;
+
+    fn instruction(accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts,
+            data: vec![],
+        }
+    }
+
+    #[test]
+    fn test_orders_instructions_and_collects_signers() {
+        let payer = Pubkey::new_unique();
+        let settler = Pubkey::new_unique();
+
+        let setup = instruction(vec![AccountMeta::new(payer, true)]);
+        let core = instruction(vec![
+            AccountMeta::new(settler, true),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ]);
+        let cleanup = instruction(vec![AccountMeta::new(payer, true)]);
+
+        let plan = DarklakeTxPlan::new(
+            payer,
+            vec![],
+            vec![setup.clone()],
+            core.clone(),
+            vec![cleanup.clone()],
+            vec![],
+        );
+
+        assert_eq!(plan.instructions(), vec![setup, core, cleanup]);
+        assert_eq!(plan.signers, vec![payer, settler]);
+
+        let transaction = plan.compile(Hash::default()).unwrap();
+        assert_eq!(transaction.message.static_account_keys()[0], payer);
+        assert_eq!(transaction.message.header().num_required_signatures, 2);
+    }
+}
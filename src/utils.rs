@@ -1,33 +1,102 @@
 use anchor_lang::{Result, solana_program::example_mocks::solana_sdk::system_instruction};
 use anchor_spl::token::spl_token::instruction::{close_account, sync_native};
-use anyhow::Result as AnyhowResult;
+use anyhow::{Context, Result as AnyhowResult, bail};
 use password_hash::rand_core::{OsRng, RngCore};
-use solana_rpc_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     address_lookup_table::AddressLookupTableAccount,
-    address_lookup_table::state::AddressLookupTable, instruction::Instruction, pubkey::Pubkey,
+    hash::Hash,
+    instruction::Instruction,
+    message::{VersionedMessage, v0},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
 };
 use spl_associated_token_account::get_associated_token_address;
 use spl_token::native_mint;
 use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
 
-use crate::constants::{DEVNET_LOOKUP, MAINNET_LOOKUP};
+use crate::{amm::PreflightIssue, constants::BPS_DENOMINATOR};
 
+/// Transfer fee a Token-2022 mint withholds when `pre_fee_amount` is transferred at `epoch`
+///
+/// The older fee applies until the epoch of the newer fee, so fee changes scheduled by the fee
+/// authority are only priced in once they are active.
 pub(crate) fn get_transfer_fee(
     transfer_fee_config: Option<TransferFeeConfig>,
     pre_fee_amount: u64,
     epoch: u64,
-) -> Result<u64> {
-    if transfer_fee_config.is_none() {
+) -> AnyhowResult<u64> {
+    let Some(transfer_fee_config) = transfer_fee_config else {
         return Ok(0);
+    };
+
+    let transfer_fee = if epoch >= u64::from(transfer_fee_config.newer_transfer_fee.epoch) {
+        transfer_fee_config.newer_transfer_fee
+    } else {
+        transfer_fee_config.older_transfer_fee
+    };
+
+    transfer_fee
+        .calculate_fee(pre_fee_amount)
+        .context("Transfer fee overflow")
+}
+
+/// Apply a slippage tolerance (in basis points) to an expected output amount
+pub(crate) fn get_min_out_with_slippage(out_amount: u64, slippage_bps: u16) -> AnyhowResult<u64> {
+    if slippage_bps as u64 > BPS_DENOMINATOR {
+        anyhow::bail!("Slippage must be at most {} bps", BPS_DENOMINATOR);
     }
 
-    let fee = transfer_fee_config
-        .unwrap()
-        .calculate_epoch_fee(epoch, pre_fee_amount)
-        .unwrap();
+    let min_out = (out_amount as u128) * (BPS_DENOMINATOR - slippage_bps as u64) as u128
+        / BPS_DENOMINATOR as u128;
+
+    Ok(min_out as u64)
+}
+
+/// Compare the user's balances with what a transaction needs
+///
+/// # Arguments
+/// * `token_balances` - `(mint, token_account, balance, required)` for every token the transaction
+///   transfers from the user, `balance` is `None` if the token account does not exist
+/// * `sol_balance` - The user's lamports
+/// * `required_lamports` - Lamports the transaction spends, excluding transaction fees
+///
+/// # Returns
+/// Returns every `PreflightIssue` found, empty if the transaction can be funded
+pub(crate) fn get_preflight_issues(
+    token_balances: &[(Pubkey, Pubkey, Option<u64>, u64)],
+    sol_balance: u64,
+    required_lamports: u64,
+) -> Vec<PreflightIssue> {
+    let mut issues = Vec::new();
+
+    for (mint, token_account, balance, required) in token_balances {
+        match balance {
+            None => issues.push(PreflightIssue::MissingTokenAccount {
+                mint: *mint,
+                token_account: *token_account,
+            }),
+            Some(balance) if balance < required => {
+                issues.push(PreflightIssue::InsufficientTokenBalance {
+                    mint: *mint,
+                    token_account: *token_account,
+                    required: *required,
+                    available: *balance,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    if sol_balance < required_lamports {
+        issues.push(PreflightIssue::InsufficientSol {
+            required: required_lamports,
+            available: sol_balance,
+        });
+    }
 
-    Ok(fee)
+    issues
 }
 
 /// Generate a random 8-byte salt for order uniqueness
@@ -105,27 +174,166 @@ pub fn convert_string_to_bytes_array(s: &str, length: usize) -> AnyhowResult<Vec
     Ok(bytes)
 }
 
-pub async fn get_address_lookup_table(
-    rpc_client: &RpcClient,
-    is_devnet: bool,
-) -> AnyhowResult<AddressLookupTableAccount> {
-    let alt_pubkey = if is_devnet {
-        DEVNET_LOOKUP
-    } else {
-        MAINNET_LOOKUP
+/// Compile an unsigned v0 transaction, failing if it does not fit into a packet once signed
+pub(crate) fn compile_v0_transaction(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> AnyhowResult<VersionedTransaction> {
+    let message_v0 = v0::Message::try_compile(
+        payer,
+        instructions,
+        address_lookup_table_accounts,
+        recent_blockhash,
+    )?;
+
+    let transaction = VersionedTransaction {
+        signatures: vec![
+            Signature::default();
+            usize::from(message_v0.header.num_required_signatures)
+        ],
+        message: VersionedMessage::V0(message_v0),
     };
 
-    let alt_account = rpc_client
-        .get_account(&alt_pubkey)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to get address lookup table: {}", e))?;
+    let transaction_size = bincode::serialized_size(&transaction)? as usize;
+    if transaction_size > PACKET_DATA_SIZE {
+        bail!(
+            "Transaction is {} bytes once signed, the limit is {}",
+            transaction_size,
+            PACKET_DATA_SIZE
+        );
+    }
+
+    Ok(VersionedTransaction {
+        signatures: vec![],
+        message: transaction.message,
+    })
+}
 
-    let table = AddressLookupTable::deserialize(&alt_account.data)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::transfer_fee_config;
 
-    let address_lookup_table = AddressLookupTableAccount {
-        key: alt_pubkey,
-        addresses: table.addresses.to_vec(),
-    };
+    #[test]
+    fn test_get_transfer_fee_uses_fee_of_epoch() {
+        let transfer_fee_config = transfer_fee_config(100, 200, 10);
 
-    Ok(address_lookup_table)
+        assert_eq!(get_transfer_fee(None, 1_000_000, 0).unwrap(), 0);
+        assert_eq!(
+            get_transfer_fee(Some(transfer_fee_config), 1_000_000, 9).unwrap(),
+            10_000
+        );
+        assert_eq!(
+            get_transfer_fee(Some(transfer_fee_config), 1_000_000, 10).unwrap(),
+            20_000
+        );
+        // fees round up
+        assert_eq!(
+            get_transfer_fee(Some(transfer_fee_config), 101, 0).unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_get_min_out_with_slippage() {
+        assert_eq!(get_min_out_with_slippage(1_000_000, 0).unwrap(), 1_000_000);
+        assert_eq!(get_min_out_with_slippage(1_000_000, 50).unwrap(), 995_000);
+        assert_eq!(get_min_out_with_slippage(999, 100).unwrap(), 989);
+        assert_eq!(
+            get_min_out_with_slippage(u64::MAX, 1).unwrap(),
+            18_444_899_399_302_180_659
+        );
+        assert!(get_min_out_with_slippage(1_000, 10_001).is_err());
+    }
+
+    #[test]
+    fn test_get_preflight_issues() {
+        let (mint_a, mint_b, mint_c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (account_a, account_b, account_c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        assert!(
+            get_preflight_issues(&[(mint_a, account_a, Some(1_000), 1_000)], 5_000, 5_000)
+                .is_empty()
+        );
+
+        let issues = get_preflight_issues(
+            &[
+                (mint_a, account_a, Some(1_000), 1_000),
+                (mint_b, account_b, None, 1),
+                (mint_c, account_c, Some(999), 1_000),
+            ],
+            4_999,
+            5_000,
+        );
+        assert_eq!(
+            issues,
+            vec![
+                PreflightIssue::MissingTokenAccount {
+                    mint: mint_b,
+                    token_account: account_b,
+                },
+                PreflightIssue::InsufficientTokenBalance {
+                    mint: mint_c,
+                    token_account: account_c,
+                    required: 1_000,
+                    available: 999,
+                },
+                PreflightIssue::InsufficientSol {
+                    required: 5_000,
+                    available: 4_999,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compile_v0_transaction_uses_lookup_tables_to_fit_packet() {
+        let payer = Pubkey::new_unique();
+        let accounts: Vec<Pubkey> = (0..40).map(|_| Pubkey::new_unique()).collect();
+        let instruction = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: accounts
+                .iter()
+                .map(|account| solana_sdk::instruction::AccountMeta::new_readonly(*account, false))
+                .collect(),
+            data: vec![],
+        };
+
+        assert!(
+            compile_v0_transaction(
+                &payer,
+                std::slice::from_ref(&instruction),
+                &[],
+                Hash::default()
+            )
+            .is_err()
+        );
+
+        let address_lookup_table_account = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: accounts,
+        };
+        let transaction = compile_v0_transaction(
+            &payer,
+            &[instruction],
+            &[address_lookup_table_account],
+            Hash::default(),
+        )
+        .unwrap();
+        assert!(transaction.signatures.is_empty());
+        assert_eq!(
+            transaction.message.address_table_lookups().unwrap().len(),
+            1
+        );
+    }
 }